@@ -0,0 +1,355 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::{
+    DayCount, Loan, Money, PaymentFrequency, PaymentSchedule, PenaltyRule, PrepaymentStrategy, RateMode,
+    RepaymentMethod, RoundingStrategy, ScenarioError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    Missing(&'static str),
+    Invalid { field: &'static str, reason: &'static str },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Missing(field) => write!(f, "missing loan field `{}`", field),
+            BuildError::Invalid { field, reason } => write!(f, "invalid loan field `{}`: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Named alternative to the positional `Loan::new`. `done_months` defaults to 0
+/// and `method` to equal principal; everything else is required.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoanBuilder {
+    principal: Option<Decimal>,
+    annual_rate: Option<Decimal>,
+    done_months: u32,
+    total_months: Option<u32>,
+    start_date: Option<NaiveDate>,
+    payment_day: Option<u8>,
+    method: Option<RepaymentMethod>,
+    frequency: PaymentFrequency,
+    rate_mode: RateMode,
+    rate_schedule: Vec<(u32, Decimal)>,
+    rate_floor: Option<Decimal>,
+    rate_cap: Option<Decimal>,
+    day_count: DayCount,
+    prepayment_penalty: Option<PenaltyRule>,
+    grace_months: u32,
+    first_payment_date: Option<NaiveDate>,
+    offset_balance: Decimal,
+    offset_changes: Vec<(NaiveDate, Decimal)>,
+    annual_prepayment_limit: Option<Decimal>,
+    balloon: Option<Decimal>,
+    #[serde(default, deserialize_with = "crate::rounding_strategy::deserialize_option")]
+    rounding: Option<RoundingStrategy>,
+    currency_scale: Option<u32>,
+    // Only used by `build_with_schedule`; scenario files list these beside the
+    // loan table instead.
+    #[serde(skip)]
+    rate_changes: Vec<(u32, Decimal)>,
+    #[serde(skip)]
+    early_payments: Vec<(u32, Decimal, PrepaymentStrategy)>,
+}
+
+impl LoanBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn principal(mut self, principal: impl Into<Money>) -> Self {
+        self.principal = Some(principal.into().0);
+        self
+    }
+
+    pub fn annual_rate(mut self, annual_rate: Decimal) -> Self {
+        self.annual_rate = Some(annual_rate);
+        self
+    }
+
+    pub fn done_months(mut self, done_months: u32) -> Self {
+        self.done_months = done_months;
+        self
+    }
+
+    pub fn total_months(mut self, total_months: u32) -> Self {
+        self.total_months = Some(total_months);
+        self
+    }
+
+    /// `total_months(years * 12)`.
+    pub fn years(self, years: u32) -> Self {
+        self.total_months(years.saturating_mul(12))
+    }
+
+    /// `total_months(years * 12 + months)`, for terms like 2 years 6 months.
+    pub fn years_months(self, years: u32, months: u32) -> Self {
+        self.total_months(years.saturating_mul(12).saturating_add(months))
+    }
+
+    /// `done_months(years * 12)`.
+    pub fn done_years(self, years: u32) -> Self {
+        self.done_months(years.saturating_mul(12))
+    }
+
+    pub fn start_date(mut self, start_date: NaiveDate) -> Self {
+        self.start_date = Some(start_date);
+        self
+    }
+
+    /// Defaults to the day of `start_date`.
+    pub fn payment_day(mut self, payment_day: u8) -> Self {
+        self.payment_day = Some(payment_day);
+        self
+    }
+
+    pub fn method(mut self, method: RepaymentMethod) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    pub fn frequency(mut self, frequency: PaymentFrequency) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn rate_mode(mut self, rate_mode: RateMode) -> Self {
+        self.rate_mode = rate_mode;
+        self
+    }
+
+    /// `(from_period, rate)` steps applied as the schedule is generated.
+    pub fn rate_schedule(mut self, rate_schedule: Vec<(u32, Decimal)>) -> Self {
+        self.rate_schedule = rate_schedule;
+        self
+    }
+
+    pub fn rate_floor(mut self, rate_floor: Decimal) -> Self {
+        self.rate_floor = Some(rate_floor);
+        self
+    }
+
+    pub fn rate_cap(mut self, rate_cap: Decimal) -> Self {
+        self.rate_cap = Some(rate_cap);
+        self
+    }
+
+    pub fn day_count(mut self, day_count: DayCount) -> Self {
+        self.day_count = day_count;
+        self
+    }
+
+    pub fn prepayment_penalty(mut self, rule: PenaltyRule) -> Self {
+        self.prepayment_penalty = Some(rule);
+        self
+    }
+
+    pub fn grace_months(mut self, grace_months: u32) -> Self {
+        self.grace_months = grace_months;
+        self
+    }
+
+    /// Disburse on `start_date` but make the first payment on `date`, with a
+    /// prorated stub period in between. `payment_day` then defaults to its day.
+    pub fn first_payment_date(mut self, date: NaiveDate) -> Self {
+        self.first_payment_date = Some(date);
+        self
+    }
+
+    pub fn offset_balance(mut self, offset_balance: impl Into<Money>) -> Self {
+        self.offset_balance = offset_balance.into().0;
+        self
+    }
+
+    /// `(effective date, offset balance)` updates for a varying offset account.
+    pub fn offset_changes(mut self, offset_changes: Vec<(NaiveDate, Decimal)>) -> Self {
+        self.offset_changes = offset_changes;
+        self
+    }
+
+    pub fn annual_prepayment_limit(mut self, limit: impl Into<Money>) -> Self {
+        self.annual_prepayment_limit = Some(limit.into().0);
+        self
+    }
+
+    pub fn balloon(mut self, balloon: impl Into<Money>) -> Self {
+        self.balloon = Some(balloon.into().0);
+        self
+    }
+
+    pub fn rounding(mut self, rounding: RoundingStrategy) -> Self {
+        self.rounding = Some(rounding);
+        self
+    }
+
+    pub fn currency_scale(mut self, currency_scale: u32) -> Self {
+        self.currency_scale = Some(currency_scale);
+        self
+    }
+
+    /// `(from_period, rate)` changes for `build_with_schedule` to apply via
+    /// `adjust_rate`, in order.
+    pub fn rate_changes(mut self, rate_changes: Vec<(u32, Decimal)>) -> Self {
+        self.rate_changes = rate_changes;
+        self
+    }
+
+    /// `(period, amount, strategy)` prepayments for `build_with_schedule`,
+    /// applied as by `apply_early_payments`.
+    pub fn early_payments(mut self, early_payments: Vec<(u32, Decimal, PrepaymentStrategy)>) -> Self {
+        self.early_payments = early_payments;
+        self
+    }
+
+    /// Builds the loan and its schedule in one go: generates the schedule, then
+    /// applies the configured rate changes followed by the early payments.
+    pub fn build_with_schedule(mut self) -> Result<(Loan, Vec<PaymentSchedule>), ScenarioError> {
+        let rate_changes = std::mem::take(&mut self.rate_changes);
+        let early_payments = std::mem::take(&mut self.early_payments);
+        let mut loan = self.build().map_err(ScenarioError::Build)?;
+        let mut schedule = loan.generate_schedule();
+
+        for (from_period, rate) in rate_changes {
+            loan.adjust_rate(rate, from_period, &mut schedule)
+                .map_err(ScenarioError::RateChange)?;
+        }
+        loan.apply_early_payments(&early_payments, &mut schedule)
+            .map_err(ScenarioError::EarlyPayment)?;
+
+        Ok((loan, schedule))
+    }
+
+    pub fn build(self) -> Result<Loan, BuildError> {
+        let principal = self.principal.ok_or(BuildError::Missing("principal"))?;
+        let annual_rate = self.annual_rate.ok_or(BuildError::Missing("annual_rate"))?;
+        let total_months = self.total_months.ok_or(BuildError::Missing("total_months"))?;
+        let start_date = self.start_date.ok_or(BuildError::Missing("start_date"))?;
+
+        if principal <= Decimal::ZERO {
+            return Err(BuildError::Invalid {
+                field: "principal",
+                reason: "must be positive",
+            });
+        }
+
+        if annual_rate < Decimal::ZERO {
+            return Err(BuildError::Invalid {
+                field: "annual_rate",
+                reason: "must not be negative",
+            });
+        }
+
+        if let (Some(floor), Some(cap)) = (self.rate_floor, self.rate_cap) {
+            if floor > cap {
+                return Err(BuildError::Invalid {
+                    field: "rate_floor",
+                    reason: "must not exceed rate_cap",
+                });
+            }
+        }
+
+        if let Some(0 | 32..) = self.payment_day {
+            return Err(BuildError::Invalid {
+                field: "payment_day",
+                reason: "must be between 1 and 31",
+            });
+        }
+
+        if let Some(first_payment_date) = self.first_payment_date {
+            if first_payment_date <= start_date {
+                return Err(BuildError::Invalid {
+                    field: "first_payment_date",
+                    reason: "must be after start_date",
+                });
+            }
+        }
+
+        if let Some(balloon) = self.balloon {
+            if balloon < Decimal::ZERO || balloon >= principal {
+                return Err(BuildError::Invalid {
+                    field: "balloon",
+                    reason: "must be at least zero and less than principal",
+                });
+            }
+        }
+
+        if self
+            .rate_schedule
+            .iter()
+            .any(|(period, _)| *period <= self.done_months || *period > total_months)
+        {
+            return Err(BuildError::Invalid {
+                field: "rate_schedule",
+                reason: "periods must fall within the remaining term",
+            });
+        }
+
+        if let Some(29..) = self.currency_scale {
+            return Err(BuildError::Invalid {
+                field: "currency_scale",
+                reason: "must be at most 28",
+            });
+        }
+
+        if self.grace_months >= total_months {
+            return Err(BuildError::Invalid {
+                field: "grace_months",
+                reason: "must be less than total_months",
+            });
+        }
+
+        let mut loan = Loan::new(
+            principal,
+            annual_rate,
+            self.done_months,
+            total_months,
+            start_date,
+            self.method.unwrap_or(RepaymentMethod::EqualPrincipal),
+        )?;
+        if let Some(first_payment_date) = self.first_payment_date {
+            loan.first_payment_date = Some(first_payment_date);
+            loan.payment_day = first_payment_date.day() as u8;
+        }
+        if let Some(payment_day) = self.payment_day {
+            loan.payment_day = payment_day;
+        }
+        loan.frequency = self.frequency;
+        loan.rate_mode = self.rate_mode;
+        loan.rate_schedule = self.rate_schedule;
+        loan.rate_floor = self.rate_floor;
+        loan.rate_cap = self.rate_cap;
+        loan.day_count = self.day_count;
+        loan.prepayment_penalty = self.prepayment_penalty;
+
+        loan.grace_months = self.grace_months;
+        loan.offset_balance = self.offset_balance;
+        loan.offset_changes = self.offset_changes;
+        loan.annual_prepayment_limit = self.annual_prepayment_limit;
+        loan.balloon = self.balloon;
+        if let Some(rounding) = self.rounding {
+            loan.rounding = rounding;
+        }
+        if let Some(currency_scale) = self.currency_scale {
+            loan.currency_scale = currency_scale;
+        }
+        if self.rounding.is_some() || self.balloon.is_some() || self.currency_scale.is_some() || self.grace_months > 0 {
+            // Spread over the periods after grace, as the schedule itself does.
+            let grace = self.grace_months.saturating_sub(self.done_months);
+            let amortizing = principal - self.balloon.unwrap_or_default();
+            let periods = loan.remaining_months().saturating_sub(grace).max(1);
+            loan.monthly_principal_payment = loan.round(amortizing / Decimal::from(periods));
+        }
+
+        Ok(loan)
+    }
+}
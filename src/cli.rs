@@ -0,0 +1,237 @@
+//! Command-line options, parsed by hand to keep the binary dependency-free.
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use prepayment::{PrepaymentStrategy, RepaymentMethod};
+
+pub const USAGE: &str = "\
+usage: prepayment --principal AMOUNT --rate PERCENT --months N --start-date YYYY-MM-DD
+                  [--done-months N] [--method equal-principal|equal-installment]
+                  [--early-payment PERIOD:AMOUNT:shorten|reduce]... [--format table|csv|json|jsonl|ics]
+       prepayment --help";
+
+pub const HELP: &str = "\
+Prints a loan's repayment schedule, with any early payments applied. Run
+without options for the built-in example.
+
+options:
+  --principal AMOUNT           balance outstanding at the start date
+  --rate PERCENT               annual interest rate, e.g. 4.2 for 4.2%
+  --months N                   total term in months, including done months
+  --start-date YYYY-MM-DD      date payments are counted from
+  --done-months N              months already paid before the start date [default: 0]
+  --method METHOD              equal-principal or equal-installment [default: equal-principal]
+  --early-payment P:AMOUNT:S   prepay AMOUNT at absolute period P, then shorten the term or
+                               reduce the installment (S is shorten or reduce); repeatable
+  --format FORMAT              table, csv, json, jsonl or ics [default: table]
+  -h, --help                   print this help";
+
+/// Why `Cli::parse` did not produce options to run with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    /// `--help` or `-h` was given.
+    Help,
+    Invalid(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Help => f.write_str("help requested"),
+            CliError::Invalid(reason) => f.write_str(reason),
+        }
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(reason: &str) -> Self {
+        CliError::Invalid(reason.to_string())
+    }
+}
+
+impl From<String> for CliError {
+    fn from(reason: String) -> Self {
+        CliError::Invalid(reason)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+    JsonLines,
+    Ics,
+}
+
+#[derive(Debug, Clone)]
+pub struct EarlyPaymentArg {
+    pub period: u32,
+    pub amount: Decimal,
+    pub strategy: PrepaymentStrategy,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub principal: Decimal,
+    pub rate: Decimal,
+    pub months: u32,
+    pub done_months: u32,
+    pub start_date: NaiveDate,
+    pub method: RepaymentMethod,
+    pub early_payments: Vec<EarlyPaymentArg>,
+    pub format: Format,
+}
+
+impl Cli {
+    pub fn parse(args: &[String]) -> Result<Cli, CliError> {
+        let mut principal = None;
+        let mut rate = None;
+        let mut months = None;
+        let mut done_months = 0;
+        let mut start_date = None;
+        let mut method = RepaymentMethod::EqualPrincipal;
+        let mut early_payments = Vec::new();
+        let mut format = Format::Table;
+
+        if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+            return Err(CliError::Help);
+        }
+
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let Some(placeholder) = placeholder(flag) else {
+                return Err(format!("unknown option `{}`", flag).into());
+            };
+            // A following option means this one's value was left out.
+            let value = args
+                .next()
+                .filter(|value| !value.starts_with("--"))
+                .ok_or_else(|| format!("`{}` needs a value: {} {}", flag, flag, placeholder))?;
+
+            match flag.as_str() {
+                "--principal" => principal = Some(parse_value(flag, value)?),
+                "--rate" => rate = Some(parse_value(flag, value)?),
+                "--months" => months = Some(parse_value(flag, value)?),
+                "--done-months" => done_months = parse_value(flag, value)?,
+                "--start-date" => start_date = Some(parse_value(flag, value)?),
+                "--method" => method = parse_method(value)?,
+                "--early-payment" => early_payments.push(parse_early_payment(value)?),
+                "--format" => format = parse_format(value)?,
+                _ => unreachable!("`placeholder` accepted `{}`", flag),
+            }
+        }
+
+        Ok(Cli {
+            principal: principal.ok_or("missing `--principal`")?,
+            rate: rate.ok_or("missing `--rate`")?,
+            months: months.ok_or("missing `--months`")?,
+            done_months,
+            start_date: start_date.ok_or("missing `--start-date`")?,
+            method,
+            early_payments,
+            format,
+        })
+    }
+}
+
+// What goes after `flag`, as shown in `USAGE`; `None` for an unknown option.
+fn placeholder(flag: &str) -> Option<&'static str> {
+    Some(match flag {
+        "--principal" => "AMOUNT",
+        "--rate" => "PERCENT",
+        "--months" | "--done-months" => "N",
+        "--start-date" => "YYYY-MM-DD",
+        "--method" => "equal-principal|equal-installment",
+        "--early-payment" => "PERIOD:AMOUNT:shorten|reduce",
+        "--format" => "table|csv|json|jsonl|ics",
+        _ => return None,
+    })
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, flag))
+}
+
+fn parse_method(value: &str) -> Result<RepaymentMethod, String> {
+    match value {
+        "equal-principal" => Ok(RepaymentMethod::EqualPrincipal),
+        "equal-installment" => Ok(RepaymentMethod::EqualInstallment),
+        _ => Err(format!("invalid value `{}` for `--method`", value)),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, String> {
+    match value {
+        "table" => Ok(Format::Table),
+        "csv" => Ok(Format::Csv),
+        "json" => Ok(Format::Json),
+        "jsonl" => Ok(Format::JsonLines),
+        "ics" => Ok(Format::Ics),
+        _ => Err(format!("invalid value `{}` for `--format`", value)),
+    }
+}
+
+fn parse_early_payment(value: &str) -> Result<EarlyPaymentArg, String> {
+    let invalid = || format!("invalid value `{}` for `--early-payment`, expected PERIOD:AMOUNT:shorten|reduce", value);
+
+    let mut parts = value.split(':');
+    let (Some(period), Some(amount), Some(strategy), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    Ok(EarlyPaymentArg {
+        period: period.parse().map_err(|_| invalid())?,
+        amount: amount.parse().map_err(|_| invalid())?,
+        strategy: match strategy {
+            "shorten" => PrepaymentStrategy::ShortenTerm,
+            "reduce" => PrepaymentStrategy::ReduceInstallment,
+            _ => return Err(invalid()),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn help_wins_over_other_options() {
+        assert_eq!(parse(&["--principal", "1000", "-h"]).unwrap_err(), CliError::Help);
+        assert_eq!(parse(&["--help"]).unwrap_err(), CliError::Help);
+    }
+
+    #[test]
+    fn missing_value_names_the_flag_and_what_it_expects() {
+        let expected = CliError::Invalid("`--principal` needs a value: --principal AMOUNT".to_string());
+        assert_eq!(parse(&["--principal"]).unwrap_err(), expected);
+        assert_eq!(parse(&["--principal", "--rate", "4"]).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn unknown_option_is_reported_before_its_value() {
+        assert_eq!(parse(&["--bogus"]).unwrap_err(), CliError::Invalid("unknown option `--bogus`".to_string()));
+    }
+
+    #[test]
+    fn parses_repeated_early_payments() {
+        let cli = parse(&[
+            "--principal", "1000", "--rate", "4.2", "--months", "12", "--start-date", "2024-01-15",
+            "--early-payment", "3:100:shorten", "--early-payment", "5:50:reduce",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.early_payments.len(), 2);
+        assert_eq!(cli.early_payments[1].strategy, PrepaymentStrategy::ReduceInstallment);
+    }
+}
@@ -0,0 +1,323 @@
+use std::io::{self, Write};
+
+use rust_decimal::Decimal;
+
+use crate::{Loan, LoanSummary, PaymentSchedule, RepaymentMethod};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    ZhCn,
+}
+
+/// Every header and label the renderers print, so output stays in one language.
+#[derive(Debug)]
+pub struct Labels {
+    pub period: &'static str,
+    pub payment_date: &'static str,
+    pub interest_rate: &'static str,
+    pub interest: &'static str,
+    pub principal_payment: &'static str,
+    pub remaining_principal: &'static str,
+    pub total_payment: &'static str,
+    pub early_payment: &'static str,
+    pub fee: &'static str,
+    pub shorten_term: &'static str,
+    pub reduce_installment: &'static str,
+    pub equal_principal: &'static str,
+    pub equal_installment: &'static str,
+    pub principal: &'static str,
+    pub annual_rate: &'static str,
+    pub term: &'static str,
+    pub start_date: &'static str,
+    pub method: &'static str,
+    pub total_interest: &'static str,
+    pub total_principal: &'static str,
+    pub total_paid: &'static str,
+    pub total_early_payments: &'static str,
+    pub interest_saved: &'static str,
+    pub payoff_date: &'static str,
+}
+
+const EN: Labels = Labels {
+    period: "period",
+    payment_date: "payment_date",
+    interest_rate: "interest_rate",
+    interest: "interest",
+    principal_payment: "principal_payment",
+    remaining_principal: "remaining_principal",
+    total_payment: "total_payment",
+    early_payment: "early_payment",
+    fee: "fee",
+    shorten_term: "Shorten term",
+    reduce_installment: "Reduce installment",
+    equal_principal: "Equal principal",
+    equal_installment: "Equal installment",
+    principal: "Principal",
+    annual_rate: "Annual rate (%)",
+    term: "Term (months)",
+    start_date: "Start date",
+    method: "Repayment method",
+    total_interest: "Total interest",
+    total_principal: "Total principal",
+    total_paid: "Total paid",
+    total_early_payments: "Total early payments",
+    interest_saved: "Interest saved",
+    payoff_date: "Payoff date",
+};
+
+const ZH_CN: Labels = Labels {
+    period: "期数",
+    payment_date: "还款日期",
+    interest_rate: "利率",
+    interest: "利息",
+    principal_payment: "本金",
+    remaining_principal: "剩余本金",
+    total_payment: "月供",
+    early_payment: "提前还款",
+    fee: "手续费",
+    shorten_term: "缩短期限",
+    reduce_installment: "减少月供",
+    equal_principal: "等额本金",
+    equal_installment: "等额本息",
+    principal: "贷款本金",
+    annual_rate: "年利率 (%)",
+    term: "期限 (月)",
+    start_date: "起始日期",
+    method: "还款方式",
+    total_interest: "总利息",
+    total_principal: "总本金",
+    total_paid: "总还款",
+    total_early_payments: "提前还款合计",
+    interest_saved: "节省利息",
+    payoff_date: "结清日期",
+};
+
+impl Locale {
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Locale::En => &EN,
+            Locale::ZhCn => &ZH_CN,
+        }
+    }
+}
+
+impl Labels {
+    // Column order shared by the CSV, Markdown and HTML renderers.
+    fn columns(&self) -> [&'static str; 9] {
+        [
+            self.period,
+            self.payment_date,
+            self.interest_rate,
+            self.interest,
+            self.principal_payment,
+            self.remaining_principal,
+            self.total_payment,
+            self.early_payment,
+            self.fee,
+        ]
+    }
+}
+
+/// Prints the header and every row of `schedule` as a tab-aligned table.
+pub fn print_schedule(schedule: &[PaymentSchedule], locale: Locale) {
+    let labels = locale.labels();
+
+    println!(
+        "{}\t{}\t\t{}\t{}\t{}\t\t{}\t\t{}\t\t{}",
+        labels.period,
+        labels.remaining_principal,
+        labels.payment_date,
+        labels.interest_rate,
+        labels.interest,
+        labels.principal_payment,
+        labels.total_payment,
+        labels.early_payment,
+    );
+    println!("-----------------------------------------------------------");
+    for p in schedule {
+        println!("{}", p);
+    }
+}
+
+/// Amounts are written to `currency_scale` places, normally the loan's, so a
+/// whole-unit currency exports whole numbers.
+pub fn schedule_to_csv(schedule: &[PaymentSchedule], locale: Locale, currency_scale: u32) -> String {
+    let mut csv = locale.labels().columns().join(",");
+    csv.push('\n');
+
+    for p in schedule {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            p.period,
+            p.payment_date,
+            p.interest_rate,
+            amount(p.interest, currency_scale),
+            amount(p.principal_payment, currency_scale),
+            amount(p.remaining_principal, currency_scale),
+            amount(p.total_payment, currency_scale),
+            p.early_payment.map_or_else(String::new, |v| amount(v, currency_scale)),
+            p.fee.map_or_else(String::new, |v| amount(v, currency_scale)),
+        ));
+    }
+
+    csv
+}
+
+/// Writes one JSON object per row, newline-terminated, without building the
+/// whole document in memory.
+pub fn write_schedule_jsonl<W: Write>(schedule: &[PaymentSchedule], w: &mut W) -> io::Result<()> {
+    for p in schedule {
+        serde_json::to_writer(&mut *w, p)?;
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// An iCalendar file with one all-day event per payment, titled
+/// `<summary_prefix> #<period>: <total_payment>`, amounts to `currency_scale`
+/// places. Rows with an early payment add its amount to the title so they
+/// stand out.
+pub fn schedule_to_ics(schedule: &[PaymentSchedule], summary_prefix: &str, currency_scale: u32) -> String {
+    let mut ics = String::from("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//prepayment//schedule//EN\r\n");
+    let prefix = escape_ics_text(summary_prefix);
+
+    for p in schedule {
+        let date = p.payment_date.format("%Y%m%d");
+        let next_day = p.payment_date.succ_opt().unwrap_or(p.payment_date).format("%Y%m%d");
+        let summary = match p.early_payment {
+            Some(early) => format!(
+                "{} #{}: {} + early payment {}",
+                prefix,
+                p.period,
+                amount(p.total_payment, currency_scale),
+                amount(early, currency_scale)
+            ),
+            None => format!("{} #{}: {}", prefix, p.period, amount(p.total_payment, currency_scale)),
+        };
+
+        ics.push_str("BEGIN:VEVENT\r\n");
+        ics.push_str(&format!("UID:{}-{}@prepayment\r\n", p.period, date));
+        ics.push_str(&format!("DTSTAMP:{}T000000Z\r\n", date));
+        ics.push_str(&format!("DTSTART;VALUE=DATE:{}\r\n", date));
+        ics.push_str(&format!("DTEND;VALUE=DATE:{}\r\n", next_day));
+        ics.push_str(&format!("SUMMARY:{}\r\n", summary));
+        ics.push_str("END:VEVENT\r\n");
+    }
+
+    ics.push_str("END:VCALENDAR\r\n");
+    ics
+}
+
+// `value` to exactly `scale` decimal places, trailing zeros included.
+fn amount(value: Decimal, scale: u32) -> String {
+    format!("{:.*}", scale as usize, value)
+}
+
+// RFC 5545 TEXT values escape backslashes, separators and newlines.
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Amounts are written to `currency_scale` places, as by `schedule_to_csv`.
+pub fn schedule_to_markdown(schedule: &[PaymentSchedule], locale: Locale, currency_scale: u32) -> String {
+    let mut md = format!("| {} |\n", locale.labels().columns().join(" | "));
+    md.push_str("|---:|---|---:|---:|---:|---:|---:|---:|---:|\n");
+
+    for p in schedule {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            p.period,
+            p.payment_date,
+            p.interest_rate,
+            amount(p.interest, currency_scale),
+            amount(p.principal_payment, currency_scale),
+            amount(p.remaining_principal, currency_scale),
+            amount(p.total_payment, currency_scale),
+            p.early_payment.map_or_else(|| "-".to_string(), |v| amount(v, currency_scale)),
+            p.fee.map_or_else(|| "-".to_string(), |v| amount(v, currency_scale)),
+        ));
+    }
+
+    md
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; }
+th { background: #f0f0f0; }
+td.num { text-align: right; }
+tr.early-payment { background: #fff4d6; font-weight: bold; }";
+
+/// A self-contained page: loan parameters, the schedule, then the summary totals.
+/// Rows with an early payment get the `early-payment` class. Amounts are
+/// written to the loan's `currency_scale`.
+pub fn schedule_to_html(loan: &Loan, schedule: &[PaymentSchedule], summary: &LoanSummary, locale: Locale) -> String {
+    let labels = locale.labels();
+    let currency_scale = loan.currency_scale;
+    let method = match loan.method {
+        RepaymentMethod::EqualPrincipal => labels.equal_principal,
+        RepaymentMethod::EqualInstallment => labels.equal_installment,
+    };
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n{}\n</style>\n</head>\n<body>\n",
+        HTML_STYLE
+    );
+
+    html.push_str("<table class=\"loan\">\n");
+    for (label, value) in [
+        (labels.principal, amount(loan.principal, currency_scale)),
+        (labels.annual_rate, loan.annual_rate.to_string()),
+        (labels.term, loan.months.to_string()),
+        (labels.start_date, loan.start_date.to_string()),
+        (labels.method, method.to_string()),
+    ] {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<table class=\"schedule\">\n<tr>");
+    for column in labels.columns() {
+        html.push_str(&format!("<th>{}</th>", column));
+    }
+    html.push_str("</tr>\n");
+
+    for p in schedule {
+        let class = if p.early_payment.is_some() { " class=\"early-payment\"" } else { "" };
+        html.push_str(&format!(
+            "<tr{}><td class=\"num\">{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+             <td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            class,
+            p.period,
+            p.payment_date,
+            p.interest_rate,
+            amount(p.interest, currency_scale),
+            amount(p.principal_payment, currency_scale),
+            amount(p.remaining_principal, currency_scale),
+            amount(p.total_payment, currency_scale),
+            p.early_payment.map_or_else(String::new, |v| amount(v, currency_scale)),
+            p.fee.map_or_else(String::new, |v| amount(v, currency_scale)),
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<table class=\"summary\">\n");
+    for (label, value) in [
+        (labels.total_interest, amount(summary.total_interest, currency_scale)),
+        (labels.total_principal, amount(summary.total_principal, currency_scale)),
+        (labels.total_early_payments, amount(summary.total_early_payments, currency_scale)),
+        (labels.total_paid, amount(summary.total_paid, currency_scale)),
+        (labels.payoff_date, summary.payoff_date.map_or_else(String::new, |d| d.to_string())),
+    ] {
+        html.push_str(&format!("<tr><th>{}</th><td class=\"num\">{}</td></tr>\n", label, value));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+
+    html
+}
@@ -0,0 +1,117 @@
+use rust_decimal::Decimal;
+
+use crate::{annuity_payment, Loan, PaymentSchedule, PaymentStatus, RepaymentMethod};
+
+/// Produces a loan's contractual schedule one row at a time; see
+/// `Loan::schedule_iter`.
+pub(crate) struct ScheduleIter<'a> {
+    loan: &'a Loan,
+    // 1-based index into the remaining term, i.e. without `done_months`.
+    period: u32,
+    months: u32,
+    grace: u32,
+    remaining_principal: Decimal,
+    // Equal principal: the fixed principal share. Equal installment: the
+    // current installment, re-amortized whenever the rate moves.
+    installment: Decimal,
+    current_rate: Option<Decimal>,
+    cumulative_interest: Decimal,
+    cumulative_principal: Decimal,
+}
+
+impl<'a> ScheduleIter<'a> {
+    pub(crate) fn new(loan: &'a Loan) -> Self {
+        let months = loan.remaining_months();
+        let grace = loan.grace_months.saturating_sub(loan.done_months).min(months);
+        let installment = match loan.method {
+            RepaymentMethod::EqualPrincipal => {
+                loan.round((loan.principal - loan.balloon.unwrap_or_default()) / Decimal::from((months - grace).max(1)))
+            }
+            RepaymentMethod::EqualInstallment => Decimal::ZERO,
+        };
+
+        ScheduleIter {
+            loan,
+            period: 1,
+            months,
+            grace,
+            remaining_principal: loan.principal,
+            installment,
+            current_rate: None,
+            cumulative_interest: Decimal::ZERO,
+            cumulative_principal: Decimal::ZERO,
+        }
+    }
+}
+
+impl Iterator for ScheduleIter<'_> {
+    type Item = PaymentSchedule;
+
+    fn next(&mut self) -> Option<PaymentSchedule> {
+        if self.period > self.months {
+            return None;
+        }
+
+        let loan = self.loan;
+        let period = self.period;
+        let remaining_principal = self.remaining_principal;
+        let payment_date = loan.scheduled_payment_date(period - 1);
+        let annual_rate = loan.rate_for_period(period + loan.done_months, payment_date);
+
+        if loan.method == RepaymentMethod::EqualInstallment
+            && (period == self.grace + 1 || self.current_rate != Some(annual_rate))
+        {
+            // A repriced rate (or the end of the grace period) re-amortizes the
+            // balance over the months left.
+            let periodic_rate = loan.periodic_rate(annual_rate);
+            let periods = (self.months + 1).saturating_sub(period.max(self.grace + 1)).max(1);
+            let balloon = loan.balloon.unwrap_or_default();
+            self.installment = loan.round(annuity_payment(remaining_principal, periodic_rate, periods, balloon));
+            self.current_rate = Some(annual_rate);
+        }
+
+        let interest = loan.interest_for(remaining_principal, annual_rate, period + loan.done_months);
+
+        // The last period takes whatever rounding left over, and any balloon,
+        // so the balance closes to exactly zero.
+        let scheduled_principal = match loan.method {
+            RepaymentMethod::EqualPrincipal => self.installment,
+            RepaymentMethod::EqualInstallment => self.installment - interest,
+        };
+        let principal_payment = if period <= self.grace {
+            Decimal::ZERO
+        } else if period == self.months || remaining_principal < scheduled_principal {
+            remaining_principal
+        } else {
+            scheduled_principal
+        };
+
+        self.cumulative_interest += interest;
+        self.cumulative_principal += principal_payment;
+        self.remaining_principal -= principal_payment;
+        self.period += 1;
+
+        Some(PaymentSchedule {
+            period: period + loan.done_months,
+            interest,
+            principal_payment,
+            remaining_principal,
+            total_payment: loan.round(principal_payment + interest),
+            interest_rate: annual_rate,
+            payment_date,
+            early_payment: None,
+            penalty: None,
+            interest_saved: None,
+            fee: None,
+            cumulative_interest: self.cumulative_interest,
+            cumulative_principal: self.cumulative_principal,
+            status: PaymentStatus::Scheduled,
+            period_rate: loan.period_rate(annual_rate, period + loan.done_months),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.months + 1 - self.period) as usize;
+        (left, Some(left))
+    }
+}
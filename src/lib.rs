@@ -0,0 +1,2214 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+pub use rust_decimal::RoundingStrategy;
+
+mod builder;
+mod export;
+mod iter;
+mod scenario;
+mod units;
+
+pub use builder::{BuildError, LoanBuilder};
+pub use export::{
+    print_schedule, schedule_to_csv, schedule_to_html, schedule_to_ics, schedule_to_markdown, write_schedule_jsonl,
+    Labels, Locale,
+};
+pub use scenario::{
+    load_scenario, load_scenario_yaml, load_scenarios, EarlyPayment, RateChange, Scenario, ScenarioError, SchemaError,
+};
+pub use units::{Money, Period};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RepaymentMethod {
+    /// 等额本金: fixed principal each month, interest shrinks with the balance.
+    EqualPrincipal,
+    /// 等额本息: fixed total payment each month, principal share grows.
+    EqualInstallment,
+}
+
+/// What an early payment does to the rest of the schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PrepaymentStrategy {
+    /// 缩短期限: keep the installment and pay off sooner.
+    ShortenTerm,
+    /// 减少月供: keep the payoff date and lower the installment.
+    ReduceInstallment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarlyPaymentError {
+    /// The period is not after `done_months`, i.e. before the first scheduled row.
+    PeriodOutOfRange,
+    /// The extra payment exceeds the balance; `max_allowed` pays the loan off exactly.
+    Overpayment { max_allowed: Decimal },
+    /// The period lies past the end of the (possibly already shortened) schedule.
+    ScheduleExhausted,
+    /// The payment would take `year`'s prepayments past `annual_prepayment_limit`;
+    /// `remaining` is what that year still allows.
+    AnnualLimitExceeded { year: i32, remaining: Decimal },
+    /// The period, or a later one it would rewrite, is marked `Paid`.
+    PeriodPaid,
+    /// A recurring prepayment was asked to repeat every 0 months.
+    ZeroInterval,
+    /// A deferral would push the last period past `u32::MAX`.
+    TermOverflow,
+}
+
+impl fmt::Display for EarlyPaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EarlyPaymentError::PeriodOutOfRange => write!(f, "period is before the first scheduled payment"),
+            EarlyPaymentError::Overpayment { max_allowed } => {
+                write!(f, "early payment exceeds the remaining principal of {}", max_allowed)
+            }
+            EarlyPaymentError::ScheduleExhausted => write!(f, "period is past the end of the schedule"),
+            EarlyPaymentError::PeriodPaid => write!(f, "period is already marked paid"),
+            EarlyPaymentError::ZeroInterval => write!(f, "recurring early payments must be at least 1 month apart"),
+            EarlyPaymentError::TermOverflow => write!(f, "deferral extends the term past the last possible period"),
+            EarlyPaymentError::AnnualLimitExceeded { year, remaining } => {
+                write!(f, "early payment exceeds the {} prepayment allowance of {} remaining", year, remaining)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EarlyPaymentError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateChangeError {
+    /// `period` is outside the loan's `first..=last` periods.
+    PeriodOutOfRange { period: u32, first: u32, last: u32 },
+    /// A row from `period` on is already marked `Paid`.
+    PeriodPaid { period: u32 },
+}
+
+impl fmt::Display for RateChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateChangeError::PeriodOutOfRange { period, first, last } => {
+                write!(f, "rate change period {} is outside {}..={}", period, first, last)
+            }
+            RateChangeError::PeriodPaid { period } => write!(f, "rate change period {} is already marked paid", period),
+        }
+    }
+}
+
+impl std::error::Error for RateChangeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RateMode {
+    /// `annual_rate` applies for the whole term unless changed via `adjust_rate`.
+    #[default]
+    Fixed,
+    /// LPR + `spread`. Each reset is `(effective_date, lpr)`; a payment uses the
+    /// latest reset on or before its `payment_date`, or `annual_rate` before the first one.
+    Floating {
+        spread: Decimal,
+        resets: Vec<(NaiveDate, Decimal)>,
+    },
+}
+
+/// One problem found by `Loan::validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    NonPositivePrincipal(Decimal),
+    /// Rates are percentages and must lie within 0-100.
+    RateOutOfRange(Decimal),
+    DoneMonthsNotBeforeTerm { done_months: u32, months: u32 },
+    InvalidPaymentDay(u8),
+    NegativeInstallment(Decimal),
+    GraceNotBeforeTerm { grace_months: u32, months: u32 },
+    FloorAboveCap { floor: Decimal, cap: Decimal },
+    FirstPaymentNotAfterStart { first_payment_date: NaiveDate, start_date: NaiveDate },
+    /// The balloon must be non-negative and less than the principal.
+    InvalidBalloon(Decimal),
+    /// `Decimal` carries at most 28 decimal places.
+    CurrencyScaleTooLarge(u32),
+    /// A `rate_schedule` step outside the loan's `first..=last` periods.
+    RateStepOutOfRange { period: u32, first: u32, last: u32 },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::NonPositivePrincipal(principal) => write!(f, "principal {} must be positive", principal),
+            ValidationError::RateOutOfRange(rate) => write!(f, "annual rate {}% is outside 0-100%", rate),
+            ValidationError::DoneMonthsNotBeforeTerm { done_months, months } => {
+                write!(f, "done_months {} must be less than months {}", done_months, months)
+            }
+            ValidationError::InvalidPaymentDay(day) => write!(f, "payment day {} must be between 1 and 31", day),
+            ValidationError::NegativeInstallment(installment) => {
+                write!(f, "monthly principal payment {} must not be negative", installment)
+            }
+            ValidationError::GraceNotBeforeTerm { grace_months, months } => {
+                write!(f, "grace_months {} must be less than months {}", grace_months, months)
+            }
+            ValidationError::FloorAboveCap { floor, cap } => write!(f, "rate floor {} exceeds rate cap {}", floor, cap),
+            ValidationError::FirstPaymentNotAfterStart { first_payment_date, start_date } => {
+                write!(f, "first payment date {} must be after start date {}", first_payment_date, start_date)
+            }
+            ValidationError::InvalidBalloon(balloon) => {
+                write!(f, "balloon {} must be at least zero and less than the principal", balloon)
+            }
+            ValidationError::CurrencyScaleTooLarge(scale) => write!(f, "currency scale {} exceeds 28", scale),
+            ValidationError::RateStepOutOfRange { period, first, last } => {
+                write!(f, "rate schedule period {} is outside {}..={}", period, first, last)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Something about a schedule worth flagging that does not stop it from being
+/// generated; see `Loan::warnings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleWarning {
+    /// The row pays less than its interest, so the balance grows.
+    NegativeAmortization { period: u32 },
+}
+
+impl fmt::Display for ScheduleWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleWarning::NegativeAmortization { period } => {
+                write!(f, "period {} pays less than its interest; the balance grows", period)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleWarning {}
+
+/// How interest accrues within a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum DayCount {
+    /// Every month is 1/12 of a year.
+    #[default]
+    Thirty360,
+    /// `annual_rate * days_in_period / 365`, with the days taken between
+    /// consecutive payment dates.
+    Actual365,
+    /// Chinese bank convention (as at ICBC and CCB): a full monthly period
+    /// accrues 年利率 / 12, while anything else (a stub first period or a
+    /// weekly/biweekly period) accrues the daily rate 年利率 / 360 per actual day.
+    Chinese30_360,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PaymentFrequency {
+    #[default]
+    Monthly,
+    BiWeekly,
+    Weekly,
+}
+
+impl PaymentFrequency {
+    pub fn periods_per_year(self) -> u32 {
+        match self {
+            PaymentFrequency::Monthly => 12,
+            PaymentFrequency::BiWeekly => 26,
+            PaymentFrequency::Weekly => 52,
+        }
+    }
+
+    // Length of a period in days; `None` for calendar months.
+    fn days(self) -> Option<u64> {
+        match self {
+            PaymentFrequency::Monthly => None,
+            PaymentFrequency::BiWeekly => Some(14),
+            PaymentFrequency::Weekly => Some(7),
+        }
+    }
+}
+
+/// Charge for prepaying principal, computed on the prepaid amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PenaltyRule {
+    /// Percent of the prepaid amount, in the same units as `annual_rate` (1 = 1%).
+    Percentage(Decimal),
+    /// This many months of interest on the prepaid amount at that period's rate.
+    MonthsOfInterest(u32),
+}
+
+/// Periods: every `period`, `from_period`, `at_period` or `start_period`
+/// argument of `Loan`'s methods, and `PaymentSchedule::period`, is absolute.
+/// The loan's first payment is period 1; with `done_months` already paid, a
+/// freshly generated schedule's first row is period `done_months + 1`. Pass
+/// the row's `period`, never its position in the `Vec` — debug builds assert
+/// that the two line up. Period arguments take a `Period` and amounts a
+/// `Money`, or the plain `u32` and `Decimal` they convert from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Loan {
+    /// Balance outstanding at `start_date`, i.e. after the `done_months`
+    /// already paid; not the amount originally borrowed.
+    pub principal: Decimal,
+    pub annual_rate: Decimal,
+    /// With a non-monthly `frequency`, `done_months` and `months` count
+    /// payment periods at that frequency rather than calendar months.
+    pub done_months: u32,
+    pub months: u32,
+    /// Accrual anchor; payments fall on `payment_day` of each month from here,
+    /// or from `first_payment_date` when that is set.
+    pub start_date: NaiveDate,
+    /// Day of month payments are due (1-31), clamped to shorter months.
+    pub payment_day: u8,
+    pub method: RepaymentMethod,
+    #[serde(default)]
+    pub frequency: PaymentFrequency,
+    #[serde(default)]
+    pub rate_mode: RateMode,
+    /// Predefined `(from_period, rate)` steps, e.g. a teaser rate then a
+    /// step-up. Periods are absolute, like `adjust_rate`'s; from each step on
+    /// its rate replaces whatever `rate_mode` would give.
+    #[serde(default)]
+    pub rate_schedule: Vec<(u32, Decimal)>,
+    /// Contractual bounds on the repriced rate, applied to `lpr + spread` and
+    /// to rates passed to `adjust_rate`.
+    #[serde(default)]
+    pub rate_floor: Option<Decimal>,
+    #[serde(default)]
+    pub rate_cap: Option<Decimal>,
+    #[serde(default)]
+    pub day_count: DayCount,
+    #[serde(default)]
+    pub prepayment_penalty: Option<PenaltyRule>,
+    /// Interest-only (宽限期) periods at the start of the loan, counted like
+    /// `done_months` from the very first period.
+    #[serde(default)]
+    pub grace_months: u32,
+    /// When set, the loan disburses on `start_date` and the first payment falls
+    /// here instead, with that first (stub) period's interest prorated by its
+    /// length in days. Later payments step from this date.
+    #[serde(default)]
+    pub first_payment_date: Option<NaiveDate>,
+    /// Savings held in an offset account: interest accrues on the balance less
+    /// this amount (never below zero) while principal still amortizes in full.
+    #[serde(default)]
+    pub offset_balance: Decimal,
+    /// Most that may be prepaid per calendar year of payment dates, e.g. 20%
+    /// of the original principal; `make_early_payment` and `payoff` reject anything more.
+    #[serde(default)]
+    pub annual_prepayment_limit: Option<Decimal>,
+    /// Lump left to repay at maturity: the installments amortize only
+    /// `principal - balloon`, and the final row's principal includes it.
+    #[serde(default)]
+    pub balloon: Option<Decimal>,
+    /// `(effective date, offset balance)` updates; the latest one on or before
+    /// a payment date replaces `offset_balance` for that period.
+    #[serde(default)]
+    pub offset_changes: Vec<(NaiveDate, Decimal)>,
+    /// Applied to every amount rounded to `currency_scale`. Defaults to banker's rounding
+    /// (`MidpointNearestEven`), which is what `Decimal::round_dp` does.
+    #[serde(default = "default_rounding", with = "rounding_strategy")]
+    pub rounding: RoundingStrategy,
+    /// Decimal places money is rounded to: 2 for cents, 0 for currencies such
+    /// as JPY. At most 28.
+    #[serde(default = "default_currency_scale")]
+    pub currency_scale: u32,
+    pub monthly_principal_payment: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentSchedule {
+    /// Absolute period, counting `done_months`; see `Loan`.
+    pub period: u32,
+    pub interest: Decimal,
+    pub principal_payment: Decimal,
+    pub remaining_principal: Decimal,
+    pub total_payment: Decimal,
+    pub interest_rate: Decimal,
+    pub payment_date: NaiveDate,
+    pub early_payment: Option<Decimal>,
+    /// Prepayment penalty charged alongside `early_payment`, if the loan has one.
+    #[serde(default)]
+    pub penalty: Option<Decimal>,
+    /// Interest this row's early payment saved against the schedule as it was
+    /// just before the payment was applied.
+    #[serde(default)]
+    pub interest_saved: Option<Decimal>,
+    /// One-off servicing charge added via `add_fee`; already in `total_payment`.
+    #[serde(default)]
+    pub fee: Option<Decimal>,
+    /// Running totals from the first row of the schedule through this one;
+    /// `cumulative_principal` includes early payments.
+    #[serde(default)]
+    pub cumulative_interest: Decimal,
+    #[serde(default)]
+    pub cumulative_principal: Decimal,
+    #[serde(default)]
+    pub status: PaymentStatus,
+    /// Fraction of the balance charged as interest this period, as returned by
+    /// `Loan::period_rate`.
+    #[serde(default)]
+    pub period_rate: Decimal,
+}
+
+/// Whether a row is history or projection. `Paid` rows are left alone by the
+/// methods that rewrite a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PaymentStatus {
+    Paid,
+    #[default]
+    Scheduled,
+    /// A payment holiday from `defer_payment`: only a fee is due and the row's
+    /// interest is capitalized into the balance.
+    Deferred,
+}
+
+/// One tab-aligned table line, in the column order of `print_schedule`'s header.
+impl fmt::Display for PaymentSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{:<8}\t{}\t{}\t{:<8}\t{:<8}\t{:<8}\t{:<8}",
+            self.period,
+            self.remaining_principal,
+            self.payment_date,
+            self.interest_rate,
+            self.interest,
+            self.principal_payment,
+            self.total_payment,
+            self.early_payment.map_or_else(|| "-".to_string(), |v| v.to_string()),
+        )
+    }
+}
+
+/// Totals over a schedule. Early payments count towards both
+/// `total_principal` and `total_paid`; penalties only towards `total_paid`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoanSummary {
+    pub total_interest: Decimal,
+    pub total_principal: Decimal,
+    pub total_paid: Decimal,
+    pub total_early_payments: Decimal,
+    pub total_fees: Decimal,
+    /// Payments actually in the schedule, after any shortening.
+    pub number_of_payments: usize,
+    /// Payments the contract called for, `months - done_months`.
+    pub contractual_payments: usize,
+    /// How many payments shorten-term prepayments cut off the contract.
+    pub months_saved: usize,
+    pub payoff_date: Option<NaiveDate>,
+}
+
+/// Outcome of applying the same early payment with each strategy, relative
+/// to the schedule it was applied to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EarlyPaymentComparison {
+    pub interest_saved_shorten: Decimal,
+    pub interest_saved_reduce: Decimal,
+    pub months_saved_shorten: u32,
+    /// Total payment of the prepayment period once re-amortized.
+    pub new_monthly_payment_reduce: Decimal,
+}
+
+/// The loan's remaining contractual schedule against a parallel one at another
+/// payment frequency over the same length of time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrequencyComparison {
+    /// Interest the other frequency saves; negative if it costs more.
+    pub interest_saved: Decimal,
+    /// Days the other frequency pays off sooner; negative if later.
+    pub payoff_date_difference: i64,
+    /// Number of payments at the other frequency.
+    pub periods: u32,
+}
+
+/// One payment date of two loans' schedules combined by `merge_schedules`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CombinedRow {
+    pub payment_date: NaiveDate,
+    pub interest: Decimal,
+    pub principal_payment: Decimal,
+    pub total_payment: Decimal,
+    /// Each loan's balance once this date's payments are made, carried over
+    /// dates it does not pay on; `None` before its first payment.
+    pub remaining_principal_a: Option<Decimal>,
+    pub remaining_principal_b: Option<Decimal>,
+}
+
+/// Staying on the current loan after a refinancing period versus moving the
+/// balance to new terms.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefinanceSummary {
+    /// Balance moved to the new loan once the refinancing period is paid.
+    pub balance_transferred: Decimal,
+    /// Interest the current schedule still charges after that period.
+    pub interest_if_staying: Decimal,
+    pub interest_if_refinancing: Decimal,
+    pub fees: Decimal,
+    /// `interest_if_staying - interest_if_refinancing - fees`; negative when
+    /// refinancing costs more.
+    pub net_savings: Decimal,
+}
+
+/// A schedule as parallel columns, the shape charting libraries consume.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub periods: Vec<u32>,
+    pub dates: Vec<NaiveDate>,
+    pub interest: Vec<Decimal>,
+    /// Principal repaid each period, early payments included.
+    pub principal: Vec<Decimal>,
+    /// Balance once each period's payments are made.
+    pub balance: Vec<Decimal>,
+    pub cumulative_interest: Vec<Decimal>,
+}
+
+/// One calendar year of a schedule, grouped by payment date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct YearSummary {
+    pub year: i32,
+    pub interest: Decimal,
+    /// Principal repaid, early payments included.
+    pub principal: Decimal,
+    /// All cash paid, as in `total_paid`.
+    pub payments: Decimal,
+    /// Balance after the year's last payment.
+    pub ending_balance: Decimal,
+}
+
+/// A blank to fill in: zero principal at 4.0% over 360 months, starting today,
+/// equal principal, with every optional setting off. Zero principal makes it
+/// fail `validate()` until a real amount is set.
+impl Default for Loan {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        Loan::new(Decimal::ZERO, Decimal::new(40, 1), 0, 360, today, RepaymentMethod::EqualPrincipal)
+            .expect("placeholder terms are valid")
+    }
+}
+
+impl Loan {
+    pub fn new(
+        principal: impl Into<Money>,
+        annual_rate: Decimal,
+        done_months: u32,
+        months: u32,
+        start_date: NaiveDate,
+        method: RepaymentMethod,
+    ) -> Result<Self, BuildError> {
+        let Money(principal) = principal.into();
+
+        if done_months >= months {
+            return Err(BuildError::Invalid {
+                field: "done_months",
+                reason: "must be less than total_months",
+            });
+        }
+
+        // Zero is an interest-free loan; a negative rate would pay the borrower.
+        if annual_rate < Decimal::ZERO {
+            return Err(BuildError::Invalid {
+                field: "annual_rate",
+                reason: "must not be negative",
+            });
+        }
+        // Rates are percentages, as `validate` checks.
+        if annual_rate > Decimal::ONE_HUNDRED {
+            return Err(BuildError::Invalid {
+                field: "annual_rate",
+                reason: "must be at most 100",
+            });
+        }
+
+        let monthly_principal_payment = (principal / Decimal::from(months - done_months)).round_dp(2);
+        Ok(Self {
+            principal,
+            annual_rate,
+            done_months,
+            months,
+            start_date,
+            payment_day: start_date.day() as u8,
+            method,
+            frequency: PaymentFrequency::Monthly,
+            rate_mode: RateMode::Fixed,
+            rate_schedule: Vec::new(),
+            rate_floor: None,
+            rate_cap: None,
+            day_count: DayCount::Thirty360,
+            prepayment_penalty: None,
+            grace_months: 0,
+            first_payment_date: None,
+            offset_balance: Decimal::ZERO,
+            annual_prepayment_limit: None,
+            balloon: None,
+            offset_changes: Vec::new(),
+            rounding: default_rounding(),
+            currency_scale: default_currency_scale(),
+            monthly_principal_payment,
+        })
+    }
+
+    /// Every problem with the loan's parameters, rather than just the first.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if self.principal <= Decimal::ZERO {
+            errors.push(ValidationError::NonPositivePrincipal(self.principal));
+        }
+        if self.annual_rate < Decimal::ZERO || self.annual_rate > Decimal::ONE_HUNDRED {
+            errors.push(ValidationError::RateOutOfRange(self.annual_rate));
+        }
+        if self.done_months >= self.months {
+            errors.push(ValidationError::DoneMonthsNotBeforeTerm {
+                done_months: self.done_months,
+                months: self.months,
+            });
+        }
+        if !(1..=31).contains(&self.payment_day) {
+            errors.push(ValidationError::InvalidPaymentDay(self.payment_day));
+        }
+        if self.monthly_principal_payment < Decimal::ZERO {
+            errors.push(ValidationError::NegativeInstallment(self.monthly_principal_payment));
+        }
+        if self.grace_months >= self.months {
+            errors.push(ValidationError::GraceNotBeforeTerm {
+                grace_months: self.grace_months,
+                months: self.months,
+            });
+        }
+        if let (Some(floor), Some(cap)) = (self.rate_floor, self.rate_cap) {
+            if floor > cap {
+                errors.push(ValidationError::FloorAboveCap { floor, cap });
+            }
+        }
+        if let Some(balloon) = self.balloon {
+            if balloon < Decimal::ZERO || balloon >= self.principal {
+                errors.push(ValidationError::InvalidBalloon(balloon));
+            }
+        }
+        if let Some(first_payment_date) = self.first_payment_date {
+            if first_payment_date <= self.start_date {
+                errors.push(ValidationError::FirstPaymentNotAfterStart {
+                    first_payment_date,
+                    start_date: self.start_date,
+                });
+            }
+        }
+        if self.currency_scale > 28 {
+            errors.push(ValidationError::CurrencyScaleTooLarge(self.currency_scale));
+        }
+        let (first, last) = (self.done_months.saturating_add(1), self.months);
+        for &(period, _) in &self.rate_schedule {
+            if !(first..=last).contains(&period) {
+                errors.push(ValidationError::RateStepOutOfRange { period, first, last });
+            }
+        }
+
+        errors
+    }
+
+    /// Rows of `schedule` that need attention, in period order: any whose
+    /// principal is negative or whose payment (fees aside) does not cover its
+    /// interest, as after a deep reduce-installment prepayment or a deferral.
+    pub fn warnings(&self, schedule: &[PaymentSchedule]) -> Vec<ScheduleWarning> {
+        schedule
+            .iter()
+            .filter(|p| {
+                p.principal_payment < Decimal::ZERO || p.total_payment - p.fee.unwrap_or_default() < p.interest
+            })
+            .map(|p| ScheduleWarning::NegativeAmortization { period: p.period })
+            .collect()
+    }
+
+    /// Periods still to pay, `months - done_months`; zero rather than an
+    /// underflow if a hand-built loan has `done_months > months`.
+    pub fn remaining_months(&self) -> u32 {
+        self.months.saturating_sub(self.done_months)
+    }
+
+    /// Periods already paid before `start_date`, never more than `months`.
+    pub fn months_completed(&self) -> u32 {
+        self.done_months.min(self.months)
+    }
+
+    /// Rounds an amount to `currency_scale` places with the loan's rounding
+    /// strategy.
+    pub fn round(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.currency_scale, self.rounding)
+    }
+
+    // A row's cash due: principal and interest, rounded, plus any fee. A
+    // deferred row's interest is capitalized rather than paid.
+    fn total_for(&self, payment: &PaymentSchedule) -> Decimal {
+        let fee = payment.fee.unwrap_or_default();
+        match payment.status {
+            PaymentStatus::Deferred => fee,
+            PaymentStatus::Paid | PaymentStatus::Scheduled => {
+                self.round(payment.principal_payment + payment.interest) + fee
+            }
+        }
+    }
+
+    fn penalty_for(&self, prepaid: Decimal, annual_rate: Decimal) -> Option<Decimal> {
+        let penalty = match self.prepayment_penalty? {
+            PenaltyRule::Percentage(percent) => prepaid * percent / Decimal::from(100),
+            PenaltyRule::MonthsOfInterest(months) => {
+                prepaid * monthly_rate(annual_rate) * Decimal::from(months)
+            }
+        };
+
+        Some(self.round(penalty))
+    }
+
+    /// Interest for the (absolute) `period` on `balance` under the loan's day count.
+    pub fn interest_for(&self, balance: impl Into<Money>, annual_rate: Decimal, period: impl Into<Period>) -> Decimal {
+        let Money(balance) = balance.into();
+        let Period(period) = period.into();
+
+        let offset = self.offset_on(self.scheduled_payment_date(period.saturating_sub(self.done_months + 1)));
+        let balance = (balance - offset).max(Decimal::ZERO);
+        self.round(balance * self.period_rate(annual_rate, period))
+    }
+
+    /// The fraction of the balance charged as interest at absolute `period`:
+    /// the annual rate scaled by frequency, day count and any first-period stub,
+    /// unrounded. Stored on each row as `period_rate`.
+    pub fn period_rate(&self, annual_rate: Decimal, period: impl Into<Period>) -> Decimal {
+        let Period(period) = period.into();
+
+        let daily_rate = annual_rate / Decimal::from(100) / Decimal::from(360);
+        match (self.day_count, self.stub_days(period)) {
+            (DayCount::Chinese30_360, Some(days)) => daily_rate * Decimal::from(days),
+            (DayCount::Chinese30_360, None) if self.frequency.days().is_some() => {
+                daily_rate * Decimal::from(self.accrual_days(period))
+            }
+            (DayCount::Thirty360 | DayCount::Chinese30_360, stub_days) => {
+                self.prorate(self.periodic_rate(annual_rate), stub_days, period)
+            }
+            (DayCount::Actual365, stub_days) => {
+                let days = self.accrual_days(period);
+                let rate = annual_rate / Decimal::from(100) * Decimal::from(days) / Decimal::from(365);
+                self.prorate(rate, stub_days, period)
+            }
+        }
+    }
+
+    /// `annual_rate` as a monthly fraction, whatever the payment frequency.
+    pub fn effective_monthly_rate(&self) -> Decimal {
+        monthly_rate(self.annual_rate)
+    }
+
+    // Scales a full period's rate down to a stub of `stub_days`.
+    fn prorate(&self, rate: Decimal, stub_days: Option<i64>, period: u32) -> Decimal {
+        match stub_days {
+            Some(days) => rate * Decimal::from(days) / Decimal::from(self.accrual_days(period)),
+            None => rate,
+        }
+    }
+
+    // Days from disbursement to the first payment, when that first period is a
+    // stub set by `first_payment_date`.
+    fn stub_days(&self, period: u32) -> Option<i64> {
+        let first_payment_date = self.first_payment_date?;
+        (period == self.done_months + 1).then(|| (first_payment_date - self.start_date).num_days())
+    }
+
+    // Where payment dates are counted from.
+    fn payment_anchor(&self) -> NaiveDate {
+        self.first_payment_date.unwrap_or(self.start_date)
+    }
+
+    fn accrual_days(&self, period: u32) -> i64 {
+        let index = period.saturating_sub(self.done_months + 1);
+        let end = self.scheduled_payment_date(index);
+        let begin = match (index, self.frequency.days()) {
+            (0, None) => {
+                let previous_month = self
+                    .payment_anchor()
+                    .checked_sub_months(Months::new(1))
+                    .unwrap_or(NaiveDate::MIN);
+                with_day_clamped(previous_month, self.payment_day as u32)
+            }
+            (0, Some(days)) => self.payment_anchor().checked_sub_days(Days::new(days)).unwrap_or(NaiveDate::MIN),
+            _ => self.scheduled_payment_date(index - 1),
+        };
+
+        (end - begin).num_days()
+    }
+
+    fn in_grace(&self, period: u32) -> bool {
+        period <= self.grace_months
+    }
+
+    // The only conversion from an absolute `period` to a row index: rows run
+    // consecutively from `done_months + 1`. `None` for a period before that;
+    // the index may still be past the end of `schedule`.
+    fn row_index(&self, schedule: &[PaymentSchedule], period: u32) -> Option<usize> {
+        let idx = period.checked_sub(self.done_months + 1)? as usize;
+        debug_assert!(
+            schedule.get(idx).is_none_or(|p| p.period == period),
+            "row {} holds period {}, not {}: was a row index passed where an absolute period is expected, \
+             or the schedule generated for another loan?",
+            idx,
+            schedule[idx].period,
+            period,
+        );
+        Some(idx)
+    }
+
+    // `index` counts rows of the schedule from 0, i.e. not including `done_months`.
+    fn scheduled_payment_date(&self, index: u32) -> NaiveDate {
+        match self.frequency.days() {
+            None => with_day_clamped(add_months(self.payment_anchor(), index), self.payment_day as u32),
+            Some(days) => self
+                .payment_anchor()
+                .checked_add_days(Days::new(days * index as u64))
+                .unwrap_or(NaiveDate::MAX),
+        }
+    }
+
+    /// `annual_rate` (a percentage) as a rate per payment period.
+    pub fn periodic_rate(&self, annual_rate: Decimal) -> Decimal {
+        rate_per_period(annual_rate, self.frequency.periods_per_year())
+    }
+
+    pub fn rate_on(&self, date: NaiveDate) -> Decimal {
+        match &self.rate_mode {
+            RateMode::Fixed => self.annual_rate,
+            RateMode::Floating { spread, resets } => resets
+                .iter()
+                .filter(|(effective, _)| *effective <= date)
+                .max_by_key(|(effective, _)| *effective)
+                .map_or(self.annual_rate, |(_, lpr)| self.clamp_rate(lpr + spread)),
+        }
+    }
+
+    /// Offset account balance in force for a payment due on `date`.
+    pub fn offset_on(&self, date: NaiveDate) -> Decimal {
+        self.offset_changes
+            .iter()
+            .filter(|(effective, _)| *effective <= date)
+            .max_by_key(|(effective, _)| *effective)
+            .map_or(self.offset_balance, |(_, offset)| *offset)
+    }
+
+    /// Rate for absolute `period` falling due on `date`: the latest
+    /// `rate_schedule` step in force, otherwise `rate_on(date)`.
+    pub fn rate_for_period(&self, period: impl Into<Period>, date: NaiveDate) -> Decimal {
+        let Period(period) = period.into();
+
+        self.rate_schedule
+            .iter()
+            .filter(|(from_period, _)| *from_period <= period)
+            .max_by_key(|(from_period, _)| *from_period)
+            .map_or_else(|| self.rate_on(date), |(_, rate)| self.clamp_rate(*rate))
+    }
+
+    fn clamp_rate(&self, rate: Decimal) -> Decimal {
+        let rate = self.rate_floor.map_or(rate, |floor| rate.max(floor));
+        self.rate_cap.map_or(rate, |cap| rate.min(cap))
+    }
+
+    /// For a loan already in progress when only the current balance is known.
+    /// Periods are then numbered from 1 at `next_payment_date`.
+    pub fn from_remaining(
+        remaining_balance: impl Into<Money>,
+        annual_rate: Decimal,
+        remaining_months: u32,
+        next_payment_date: NaiveDate,
+    ) -> Result<Self, BuildError> {
+        Self::new(
+            remaining_balance,
+            annual_rate,
+            0,
+            remaining_months,
+            next_payment_date,
+            RepaymentMethod::EqualPrincipal,
+        )
+    }
+
+    /// Dates serialize as `YYYY-MM-DD` and decimals as strings, so nothing is
+    /// lost to float rounding on the way through JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Loan is always serializable")
+    }
+
+    pub fn from_json(s: &str) -> Result<Loan, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    pub fn generate_schedule(&self) -> Vec<PaymentSchedule> {
+        self.schedule_iter().collect()
+    }
+
+    /// The contractual schedule generated one row at a time, for long loans or
+    /// when only the first few rows or a running total are needed.
+    pub fn schedule_iter(&self) -> impl Iterator<Item = PaymentSchedule> + '_ {
+        iter::ScheduleIter::new(self)
+    }
+
+    /// Adds a `rate_schedule` step, replacing any step already at `from_period`,
+    /// so rate history can be chained onto the loan before `generate_schedule`
+    /// instead of applied afterwards with `adjust_rate`. `from_period` should lie
+    /// in `done_months + 1..=months`, as for `adjust_rate`; `validate` reports a
+    /// step outside it.
+    pub fn with_rate_change(mut self, new_rate: Decimal, from_period: impl Into<Period>) -> Loan {
+        let Period(from_period) = from_period.into();
+
+        self.rate_schedule.retain(|(period, _)| *period != from_period);
+        self.rate_schedule.push((from_period, new_rate));
+        self.rate_schedule.sort_by_key(|(period, _)| *period);
+        self
+    }
+
+    /// Reprices every row from `from_period` on. Like `make_early_payment`,
+    /// `from_period` is an absolute loan period, i.e. it counts `done_months`.
+    /// Rows are matched by their `period`, so `schedule` may be any slice of
+    /// the loan's schedule. Equal principal keeps each row's principal; equal
+    /// installment re-amortizes the balance at the new rate, as
+    /// `generate_schedule` would, so the slice must run to the end of the loan.
+    pub fn adjust_rate(
+        &mut self,
+        new_rate: Decimal,
+        from_period: impl Into<Period>,
+        schedule: &mut [PaymentSchedule],
+    ) -> Result<(), RateChangeError> {
+        let Period(from_period) = from_period.into();
+
+        let (first, last) = (self.done_months + 1, self.months);
+        if !(first..=last).contains(&from_period) {
+            return Err(RateChangeError::PeriodOutOfRange {
+                period: from_period,
+                first,
+                last,
+            });
+        }
+
+        if is_paid_from(schedule, from_period) {
+            return Err(RateChangeError::PeriodPaid { period: from_period });
+        }
+
+        let new_rate = self.clamp_rate(new_rate);
+        self.annual_rate = new_rate;
+
+        // A payment holiday keeps the interest it already capitalized.
+        for payment in schedule
+            .iter_mut()
+            .filter(|p| p.period >= from_period && p.status != PaymentStatus::Deferred)
+        {
+            payment.interest_rate = new_rate;
+            payment.period_rate = self.period_rate(new_rate, payment.period);
+            payment.interest = self.interest_for(payment.remaining_principal, new_rate, payment.period);
+            payment.total_payment = self.total_for(payment);
+        }
+
+        if self.method == RepaymentMethod::EqualInstallment {
+            if let Some(start) = schedule.iter().position(|p| p.period >= from_period) {
+                let remaining_principal = schedule[start].remaining_principal;
+                self.reamortize(&mut schedule[start..], remaining_principal);
+            }
+        }
+
+        update_cumulative_totals(schedule);
+        Ok(())
+    }
+
+    /// Prepays `extra_payment` at absolute `period` and rewrites the rows from
+    /// there per `strategy`. Rejects a period before the schedule, past its
+    /// end, or already marked paid, and an amount above the balance.
+    pub fn make_early_payment(
+        &mut self,
+        extra_payment: impl Into<Money>,
+        period: impl Into<Period>,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Money(extra_payment) = extra_payment.into();
+        let Period(period) = period.into();
+
+        self.apply_early_payment(extra_payment, period, strategy, schedule)
+            .map(|_| ())
+    }
+
+    /// `make_early_payment` keeping the term and reducing the installment.
+    /// Returns the new installment the rest of the schedule pays: the principal
+    /// share for equal principal, the whole payment for equal installment.
+    pub fn make_early_payment_reduce(
+        &mut self,
+        extra_payment: impl Into<Money>,
+        period: impl Into<Period>,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<Decimal, EarlyPaymentError> {
+        let Money(extra_payment) = extra_payment.into();
+        let Period(period) = period.into();
+
+        self.apply_early_payment(extra_payment, period, PrepaymentStrategy::ReduceInstallment, schedule)
+            .map(Option::unwrap_or_default)
+    }
+
+    // Shared body of the `make_early_payment` variants; returns the re-amortized
+    // installment when the term is kept.
+    fn apply_early_payment(
+        &mut self,
+        extra_payment: Decimal,
+        period: u32,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<Option<Decimal>, EarlyPaymentError> {
+        let mut idx = self
+            .row_index(schedule, period)
+            .ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+
+        if idx >= schedule.len() {
+            return Err(EarlyPaymentError::ScheduleExhausted);
+        }
+        if is_paid_from(schedule, period) {
+            return Err(EarlyPaymentError::PeriodPaid);
+        }
+
+        self.check_annual_limit(schedule, idx, extra_payment)?;
+
+        let mut remaining_principal = self.round(schedule[idx].remaining_principal - extra_payment);
+
+        if remaining_principal < Decimal::from(0) {
+            return Err(EarlyPaymentError::Overpayment {
+                max_allowed: schedule[idx].remaining_principal,
+            });
+        }
+
+        let start = idx;
+        let interest_before: Decimal = schedule[start..].iter().map(|p| p.interest).sum();
+
+        // A second prepayment at the same period adds to the first.
+        let row = &mut schedule[start];
+        row.early_payment = Some(row.early_payment.unwrap_or_default() + extra_payment);
+        if let Some(penalty) = self.penalty_for(extra_payment, row.interest_rate) {
+            row.penalty = Some(row.penalty.unwrap_or_default() + penalty);
+        }
+
+        // Paying off the whole balance ends the schedule here whatever the
+        // strategy, so it takes the shorten-term path and truncates after this row.
+        if strategy == PrepaymentStrategy::ShortenTerm || remaining_principal.is_zero() {
+            for payment in &mut schedule[idx..] {
+                let interest = self.interest_for(remaining_principal, payment.interest_rate, payment.period);
+                // Equal installment keeps the row's payment, so the interest
+                // saved goes to principal.
+                let scheduled_principal = match self.method {
+                    _ if payment.status == PaymentStatus::Deferred => Decimal::ZERO,
+                    RepaymentMethod::EqualPrincipal => payment.principal_payment,
+                    RepaymentMethod::EqualInstallment if self.in_grace(payment.period) => Decimal::ZERO,
+                    RepaymentMethod::EqualInstallment => {
+                        payment.total_payment - payment.fee.unwrap_or_default() - interest
+                    }
+                };
+
+                payment.remaining_principal = remaining_principal;
+                payment.principal_payment = remaining_principal.min(scheduled_principal);
+                payment.interest = interest;
+                payment.total_payment = self.total_for(payment);
+    
+                remaining_principal -= payment.principal_payment;
+                if payment.status == PaymentStatus::Deferred {
+                    remaining_principal += interest;
+                }
+
+                idx += 1;
+
+                // Each row pays at most the balance it opens with, so the balance
+                // reaches exactly zero on the row that closes it; drop the rest
+                // rather than leaving zero-principal rows behind.
+                if remaining_principal.is_zero() {
+                    schedule.truncate(idx);
+                    break;
+                }
+            }
+        }
+
+        let installment = (strategy == PrepaymentStrategy::ReduceInstallment)
+            .then(|| self.reamortize(&mut schedule[idx..], remaining_principal));
+
+        let interest_after: Decimal = schedule[start..].iter().map(|p| p.interest).sum();
+        let saved = interest_before - interest_after;
+        schedule[start].interest_saved = Some(schedule[start].interest_saved.unwrap_or_default() + saved);
+
+        update_cumulative_totals(schedule);
+        Ok(installment)
+    }
+
+    // Rejects prepaying `amount` at row `idx` if that would take the calendar
+    // year's prepayments past `annual_prepayment_limit`.
+    fn check_annual_limit(
+        &self,
+        schedule: &[PaymentSchedule],
+        idx: usize,
+        amount: Decimal,
+    ) -> Result<(), EarlyPaymentError> {
+        let Some(limit) = self.annual_prepayment_limit else {
+            return Ok(());
+        };
+
+        let year = schedule[idx].payment_date.year();
+        let prepaid: Decimal = schedule
+            .iter()
+            .filter(|p| p.payment_date.year() == year)
+            .filter_map(|p| p.early_payment)
+            .sum();
+        if prepaid + amount > limit {
+            return Err(EarlyPaymentError::AnnualLimitExceeded {
+                year,
+                remaining: (limit - prepaid).max(Decimal::ZERO),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Non-mutating `make_early_payment`: returns the schedule with the payment
+    /// applied and leaves `schedule` as it was, so several what-ifs can branch
+    /// from one baseline.
+    pub fn with_early_payment(
+        &self,
+        extra_payment: impl Into<Money>,
+        period: impl Into<Period>,
+        strategy: PrepaymentStrategy,
+        schedule: &[PaymentSchedule],
+    ) -> Result<Vec<PaymentSchedule>, EarlyPaymentError> {
+        let Money(extra_payment) = extra_payment.into();
+        let Period(period) = period.into();
+
+        let mut rows = schedule.to_vec();
+        self.clone().make_early_payment(extra_payment, period, strategy, &mut rows)?;
+        Ok(rows)
+    }
+
+    /// Copies of the loan and `schedule` to branch a what-if from, without
+    /// re-running `generate_schedule`. Rows own all their data, so the copy is
+    /// independent; `schedule.to_vec()` alone is enough when the loan is not
+    /// mutated. Restoring is assigning the copies back.
+    pub fn fork(&self, schedule: &[PaymentSchedule]) -> (Loan, Vec<PaymentSchedule>) {
+        (self.clone(), schedule.to_vec())
+    }
+
+    // Re-amortizes `remaining_principal` over the amortizing rows of `rows`,
+    // which run to the end of the schedule, the way `generate_schedule` would:
+    // equal principal spreads it evenly, equal installment recomputes the
+    // annuity payment, again whenever a row's rate differs from the last.
+    // `monthly_principal_payment` stays the loan's original installment. The
+    // rows themselves are the remaining term, so a schedule an earlier
+    // prepayment shortened keeps its payoff period and the last row closes the
+    // balance. Deferred rows stay payment holidays and capitalize their
+    // interest on the new balance. Returns the new installment: the principal
+    // share for equal principal, the whole payment for equal installment.
+    fn reamortize(&self, rows: &mut [PaymentSchedule], mut remaining_principal: Decimal) -> Decimal {
+        let balloon = self.balloon.unwrap_or_default();
+        let amortizes = |p: &PaymentSchedule| !self.in_grace(p.period) && p.status != PaymentStatus::Deferred;
+        let remaining_period = rows.iter().filter(|p| amortizes(p)).count().max(1);
+        // Amortizing rows from the current one to the end.
+        let mut periods_left = remaining_period as u32;
+        let mut installment = match self.method {
+            RepaymentMethod::EqualPrincipal => {
+                self.round((remaining_principal - balloon).max(Decimal::ZERO) / Decimal::from(remaining_period))
+            }
+            RepaymentMethod::EqualInstallment => Decimal::ZERO,
+        };
+        let mut first_installment = None;
+        let mut current_rate = None;
+        let last = rows.len().saturating_sub(1);
+
+        for (i, payment) in rows.iter_mut().enumerate() {
+            let interest = self.interest_for(remaining_principal, payment.interest_rate, payment.period);
+
+            if payment.status == PaymentStatus::Deferred {
+                payment.remaining_principal = remaining_principal;
+                payment.principal_payment = Decimal::ZERO;
+                payment.interest = interest;
+                payment.total_payment = self.total_for(payment);
+                remaining_principal += interest;
+                // The larger balance is re-amortized once payments resume.
+                current_rate = None;
+                continue;
+            }
+
+            let in_grace = self.in_grace(payment.period);
+            if self.method == RepaymentMethod::EqualInstallment
+                && !in_grace
+                && current_rate != Some(payment.interest_rate)
+            {
+                let periodic_rate = self.periodic_rate(payment.interest_rate);
+                let periods = periods_left.max(1);
+                installment = self.round(annuity_payment(remaining_principal, periodic_rate, periods, balloon));
+                current_rate = Some(payment.interest_rate);
+            }
+            if !in_grace {
+                first_installment.get_or_insert(installment);
+                periods_left = periods_left.saturating_sub(1);
+            }
+
+            let scheduled_principal = match self.method {
+                RepaymentMethod::EqualPrincipal => installment,
+                RepaymentMethod::EqualInstallment => installment - interest,
+            };
+            payment.remaining_principal = remaining_principal;
+            payment.principal_payment = if in_grace {
+                Decimal::ZERO
+            } else if i == last || remaining_principal < scheduled_principal {
+                remaining_principal
+            } else {
+                scheduled_principal
+            };
+            payment.interest = interest;
+            payment.total_payment = self.total_for(payment);
+
+            remaining_principal -= payment.principal_payment;
+        }
+
+        first_installment.unwrap_or(installment)
+    }
+
+    // Restores rows a shortened term dropped, so a larger balance can be spread
+    // over the full contractual term again.
+    fn extend_to_term(&self, schedule: &mut Vec<PaymentSchedule>) {
+        self.extend_to(schedule, self.months);
+    }
+
+    // Appends placeholder rows, to be filled in by `reamortize`, until the
+    // schedule reaches `last_period`.
+    fn extend_to(&self, schedule: &mut Vec<PaymentSchedule>, last_period: u32) {
+        while let Some(last) = schedule.last() {
+            if last.period >= last_period {
+                break;
+            }
+
+            let period = last.period + 1;
+            let interest_rate = last.interest_rate;
+            schedule.push(PaymentSchedule {
+                period,
+                interest: Decimal::ZERO,
+                principal_payment: Decimal::ZERO,
+                remaining_principal: Decimal::ZERO,
+                total_payment: Decimal::ZERO,
+                interest_rate,
+                payment_date: self.scheduled_payment_date(period - self.done_months - 1),
+                early_payment: None,
+                penalty: None,
+                interest_saved: None,
+                fee: None,
+                cumulative_interest: Decimal::ZERO,
+                cumulative_principal: Decimal::ZERO,
+                status: PaymentStatus::Scheduled,
+                period_rate: self.period_rate(interest_rate, period),
+            });
+        }
+    }
+
+    /// Draws down `amount` of extra principal at `period` (construction or
+    /// credit-line loans); interest from that period accrues on the higher
+    /// balance and the rest of the term is re-amortized.
+    pub fn add_drawdown(
+        &mut self,
+        amount: impl Into<Money>,
+        period: impl Into<Period>,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Money(amount) = amount.into();
+        let Period(period) = period.into();
+
+        let idx = self
+            .row_index(schedule, period)
+            .ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+
+        if idx >= schedule.len() {
+            return Err(EarlyPaymentError::ScheduleExhausted);
+        }
+        if is_paid_from(schedule, period) {
+            return Err(EarlyPaymentError::PeriodPaid);
+        }
+
+        let remaining_principal = schedule[idx].remaining_principal + amount;
+        self.extend_to_term(schedule);
+        self.reamortize(&mut schedule[idx..], remaining_principal);
+
+        update_cumulative_totals(schedule);
+        Ok(())
+    }
+
+    /// Charges a one-off `amount` (e.g. a servicing fee) at `period`, replacing
+    /// any fee already there. It is added to that row's `total_payment`, so it
+    /// counts in `total_paid` but not in interest or principal.
+    pub fn add_fee(
+        &mut self,
+        period: impl Into<Period>,
+        amount: impl Into<Money>,
+        schedule: &mut [PaymentSchedule],
+    ) -> Result<(), EarlyPaymentError> {
+        let Period(period) = period.into();
+        let Money(amount) = amount.into();
+
+        let idx = self
+            .row_index(schedule, period)
+            .ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+        let payment = schedule.get_mut(idx).ok_or(EarlyPaymentError::ScheduleExhausted)?;
+        if payment.status == PaymentStatus::Paid {
+            return Err(EarlyPaymentError::PeriodPaid);
+        }
+
+        payment.fee = Some(amount);
+        payment.total_payment = self.total_for(payment);
+        Ok(())
+    }
+
+    /// Payment holiday: the `months` rows from `period` pay nothing, their
+    /// interest is capitalized into the balance, and the rest is re-amortized
+    /// over the remaining rows plus `months` more appended at the end (growing
+    /// `self.months` if that passes the term). This *increases* total interest,
+    /// since interest then accrues on interest, and pushes the payoff back.
+    /// Capitalized interest still counts in `total_interest_paid`. The rows are
+    /// marked `Deferred`, so later rewrites such as `add_fee` or `adjust_rate`
+    /// keep them holidays rather than charging their interest again.
+    pub fn defer_payment(
+        &mut self,
+        period: impl Into<Period>,
+        months: u32,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Period(period) = period.into();
+
+        let idx = self
+            .row_index(schedule, period)
+            .ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+        let Some(last) = schedule.last().filter(|_| idx < schedule.len()) else {
+            return Err(EarlyPaymentError::ScheduleExhausted);
+        };
+        if is_paid_from(schedule, period) {
+            return Err(EarlyPaymentError::PeriodPaid);
+        }
+
+        let last_period = last.period.checked_add(months).ok_or(EarlyPaymentError::TermOverflow)?;
+        self.months = self.months.max(last_period);
+        self.extend_to(schedule, last_period);
+
+        for payment in &mut schedule[idx..idx + months as usize] {
+            payment.status = PaymentStatus::Deferred;
+        }
+
+        let remaining_principal = schedule[idx].remaining_principal;
+        self.reamortize(&mut schedule[idx..], remaining_principal);
+        update_cumulative_totals(schedule);
+        Ok(())
+    }
+
+    /// Like `make_early_payment`, but an overpayment is capped to the balance at
+    /// `period` (paying the loan off) instead of being rejected. Returns the
+    /// amount actually applied.
+    pub fn make_early_payment_capped(
+        &mut self,
+        extra_payment: impl Into<Money>,
+        period: impl Into<Period>,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<Decimal, EarlyPaymentError> {
+        let Money(extra_payment) = extra_payment.into();
+        let Period(period) = period.into();
+
+        match self.make_early_payment(extra_payment, period, strategy, schedule) {
+            Ok(()) => Ok(extra_payment),
+            Err(EarlyPaymentError::Overpayment { max_allowed }) => {
+                // Paying everything off always drops the rest of the term.
+                self.make_early_payment(max_allowed, period, PrepaymentStrategy::ShortenTerm, schedule)?;
+                Ok(max_allowed)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Applies `(period, amount, strategy)` prepayments in ascending period
+    /// order, stopping at the first one that is rejected.
+    pub fn apply_early_payments(
+        &mut self,
+        payments: &[(u32, Decimal, PrepaymentStrategy)],
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let mut payments = payments.to_vec();
+        payments.sort_by_key(|(period, _, _)| *period);
+
+        for (period, amount, strategy) in payments {
+            self.make_early_payment(amount, period, strategy, schedule)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prepays `amount` at `start_period` and every `every_n_months` after it
+    /// until the loan is paid off or the next payment would overshoot the
+    /// balance. `amount` is applied as given each time; it is not re-rounded to
+    /// whole installments.
+    pub fn make_recurring_early_payment(
+        &mut self,
+        amount: impl Into<Money>,
+        every_n_months: u32,
+        start_period: impl Into<Period>,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Money(amount) = amount.into();
+        let Period(start_period) = start_period.into();
+
+        if every_n_months == 0 {
+            return Err(EarlyPaymentError::ZeroInterval);
+        }
+
+        let mut period = start_period;
+        loop {
+            match self.make_early_payment(amount, period, strategy, schedule) {
+                Ok(()) => match period.checked_add(every_n_months) {
+                    Some(next) => period = next,
+                    None => return Ok(()),
+                },
+                Err(EarlyPaymentError::ScheduleExhausted | EarlyPaymentError::Overpayment { .. }) => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Prepays `amount` at every period whose payment date falls in `month`
+    /// (1-12), e.g. a year-end bonus each January. Periods are looked up again
+    /// after each payment, so once shortening drops the tail of the term its
+    /// months simply stop matching; a bonus larger than the balance pays the
+    /// loan off and ends the run.
+    pub fn apply_annual_bonus(
+        &mut self,
+        month: u32,
+        amount: impl Into<Money>,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Money(amount) = amount.into();
+
+        let mut after = 0;
+        while let Some(period) = schedule
+            .iter()
+            .find(|p| p.period > after && p.payment_date.month() == month)
+            .map(|p| p.period)
+        {
+            self.make_early_payment_capped(amount, period, strategy, schedule)?;
+            after = period;
+        }
+
+        Ok(())
+    }
+
+    /// The smallest constant extra payment, applied every period from the first
+    /// row of `schedule` with the term shortened, that closes the loan at or
+    /// before `target_period`. Returns zero when the schedule already ends by
+    /// then, and the whole opening balance when the target is before the first
+    /// row. `schedule` itself is left untouched.
+    pub fn extra_for_target_payoff(&self, target_period: impl Into<Period>, schedule: &[PaymentSchedule]) -> Decimal {
+        let Period(target_period) = target_period.into();
+
+        let Some(first) = schedule.first() else {
+            return Decimal::ZERO;
+        };
+
+        if self.payoff_period_with_extra(Decimal::ZERO, schedule) <= target_period {
+            return Decimal::ZERO;
+        }
+
+        // Search whole cents: paying the full balance up front always works.
+        let cent = Decimal::new(1, 2);
+        let mut low = 0i64;
+        let mut high = (first.remaining_principal / cent).ceil().to_i64().unwrap_or(i64::MAX);
+        if target_period < first.period {
+            return Decimal::from(high) * cent;
+        }
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.payoff_period_with_extra(Decimal::from(mid) * cent, schedule) <= target_period {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Decimal::from(high) * cent
+    }
+
+    /// The period at which the balance reaches zero if `extra_per_month` is
+    /// prepaid every period from the first row of `schedule`, shortening the
+    /// term. Works on a copy; a zero extra returns the schedule's final period.
+    pub fn payoff_period_with_extra(&self, extra_per_month: impl Into<Money>, schedule: &[PaymentSchedule]) -> u32 {
+        let Money(extra_per_month) = extra_per_month.into();
+
+        self.with_constant_extra(extra_per_month, schedule)
+            .last()
+            .map_or(self.done_months, |p| p.period)
+    }
+
+    /// The smallest constant extra payment, applied every period from the first
+    /// row of `schedule` with the term shortened, that keeps total interest at or
+    /// under `max_total_interest`. `None` only when the cap is below the interest
+    /// still charged with the whole balance repaid at the first row.
+    pub fn extra_for_interest_cap(
+        &self,
+        max_total_interest: impl Into<Money>,
+        schedule: &[PaymentSchedule],
+    ) -> Option<Decimal> {
+        let Money(max_total_interest) = max_total_interest.into();
+
+        let first = schedule.first()?;
+        let interest_with = |extra: Decimal| self.total_interest_paid(&self.with_constant_extra(extra, schedule));
+
+        if interest_with(Decimal::ZERO) <= max_total_interest {
+            return Some(Decimal::ZERO);
+        }
+
+        let cent = Decimal::new(1, 2);
+        let mut low = 0i64;
+        let mut high = (first.remaining_principal / cent).ceil().to_i64().unwrap_or(i64::MAX);
+        if interest_with(Decimal::from(high) * cent) > max_total_interest {
+            return None;
+        }
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if interest_with(Decimal::from(mid) * cent) <= max_total_interest {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        Some(Decimal::from(high) * cent)
+    }
+
+    // A copy of `schedule` with `extra_per_month` prepaid every period from its
+    // first row, shortening the term, until the balance is gone.
+    fn with_constant_extra(&self, extra_per_month: Decimal, schedule: &[PaymentSchedule]) -> Vec<PaymentSchedule> {
+        let mut loan = self.clone();
+        let mut rows = schedule.to_vec();
+
+        if extra_per_month > Decimal::ZERO {
+            if let Some(first) = rows.first() {
+                let mut period = first.period;
+                while loan
+                    .make_early_payment_capped(extra_per_month, period, PrepaymentStrategy::ShortenTerm, &mut rows)
+                    .is_ok()
+                {
+                    period += 1;
+                }
+            }
+        }
+
+        rows
+    }
+
+    /// Applies the payment at the first period due on or after `date`.
+    pub fn make_early_payment_on(
+        &mut self,
+        extra_payment: impl Into<Money>,
+        date: NaiveDate,
+        strategy: PrepaymentStrategy,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<(), EarlyPaymentError> {
+        let Money(extra_payment) = extra_payment.into();
+
+        let period = schedule
+            .iter()
+            .find(|p| p.payment_date >= date)
+            .map(|p| p.period)
+            .ok_or(EarlyPaymentError::ScheduleExhausted)?;
+
+        self.make_early_payment(extra_payment, period, strategy, schedule)
+    }
+
+    /// Settles the loan at `period`: the regular installment is paid as usual and
+    /// the rest of the balance is recorded as that row's early payment, so the
+    /// balance closes to exactly zero. Returns the total cash needed that period.
+    /// The lump counts toward `annual_prepayment_limit` like any prepayment.
+    pub fn payoff(
+        &mut self,
+        period: impl Into<Period>,
+        schedule: &mut Vec<PaymentSchedule>,
+    ) -> Result<Decimal, EarlyPaymentError> {
+        let Period(period) = period.into();
+
+        let idx = self
+            .row_index(schedule, period)
+            .ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+
+        if idx >= schedule.len() {
+            return Err(EarlyPaymentError::ScheduleExhausted);
+        }
+        if is_paid_from(schedule, period) {
+            return Err(EarlyPaymentError::PeriodPaid);
+        }
+
+        let lump = schedule[idx].remaining_principal - schedule[idx].principal_payment;
+        self.check_annual_limit(schedule, idx, lump)?;
+
+        let interest_saved: Decimal = schedule[idx + 1..].iter().map(|p| p.interest).sum();
+        schedule.truncate(idx + 1);
+
+        // As with `make_early_payment`, the row's balance is what is left after
+        // the lump, so it closes to zero once the regular principal is paid.
+        let payment = &mut schedule[idx];
+        // Any prepayment already made at this row stays and the lump adds to it.
+        payment.early_payment = Some(payment.early_payment.unwrap_or_default() + lump);
+        payment.remaining_principal = payment.principal_payment;
+        if let Some(penalty) = self.penalty_for(lump, payment.interest_rate) {
+            payment.penalty = Some(payment.penalty.unwrap_or_default() + penalty);
+        }
+        payment.interest_saved = Some(payment.interest_saved.unwrap_or_default() + interest_saved);
+        let settlement =
+            payment.total_payment + payment.early_payment.unwrap_or_default() + payment.penalty.unwrap_or_default();
+
+        update_cumulative_totals(schedule);
+        Ok(settlement)
+    }
+
+    pub fn total_interest_paid(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule.iter().map(|p| p.interest).sum()
+    }
+
+    /// Interest `actual` avoids against the no-prepayment `baseline`; negative
+    /// if `actual` charges more, e.g. after a deferral.
+    pub fn total_interest_saved(&self, baseline: &[PaymentSchedule], actual: &[PaymentSchedule]) -> Decimal {
+        self.total_interest_paid(baseline) - self.total_interest_paid(actual)
+    }
+
+    /// Interest saved by prepaying `extra` at absolute `period` under each
+    /// strategy, on copies of the loan and `schedule`.
+    pub fn compare_early_payment(
+        &self,
+        extra: impl Into<Money>,
+        period: impl Into<Period>,
+        schedule: &[PaymentSchedule],
+    ) -> Result<EarlyPaymentComparison, EarlyPaymentError> {
+        let Money(extra) = extra.into();
+        let Period(period) = period.into();
+
+        let baseline_interest = self.total_interest_paid(schedule);
+
+        let mut shortened = schedule.to_vec();
+        self.clone()
+            .make_early_payment(extra, period, PrepaymentStrategy::ShortenTerm, &mut shortened)?;
+
+        let mut reduced = schedule.to_vec();
+        self.clone()
+            .make_early_payment(extra, period, PrepaymentStrategy::ReduceInstallment, &mut reduced)?;
+
+        let idx = self.row_index(schedule, period).ok_or(EarlyPaymentError::PeriodOutOfRange)?;
+
+        Ok(EarlyPaymentComparison {
+            interest_saved_shorten: baseline_interest - self.total_interest_paid(&shortened),
+            interest_saved_reduce: baseline_interest - self.total_interest_paid(&reduced),
+            months_saved_shorten: (schedule.len() - shortened.len()) as u32,
+            new_monthly_payment_reduce: reduced[idx].total_payment,
+        })
+    }
+
+    /// Compares the remaining term against the same balance, rate and length of
+    /// time paid at `other` frequency. The number of periods, the grace period
+    /// and each `rate_schedule` step are scaled by the ratio of periods per
+    /// year, rounding up, and counted from the first remaining payment.
+    pub fn compare_frequency(&self, other: PaymentFrequency) -> FrequencyComparison {
+        let scale = |periods: u32| {
+            (periods * other.periods_per_year()).div_ceil(self.frequency.periods_per_year())
+        };
+
+        let mut parallel = self.clone();
+        parallel.frequency = other;
+        parallel.done_months = 0;
+        parallel.months = scale(self.remaining_months()).max(1);
+        parallel.grace_months = scale(self.grace_months.saturating_sub(self.done_months));
+        // A step already in force when the remaining term starts applies from
+        // its first period.
+        parallel.rate_schedule = self
+            .rate_schedule
+            .iter()
+            .map(|(from_period, rate)| (scale(from_period.saturating_sub(self.done_months + 1)) + 1, *rate))
+            .collect();
+        parallel.monthly_principal_payment = parallel.round(parallel.principal / Decimal::from(parallel.months));
+
+        let baseline = self.generate_schedule();
+        let alternative = parallel.generate_schedule();
+        let payoff = |schedule: &[PaymentSchedule]| schedule.last().map_or(self.start_date, |p| p.payment_date);
+
+        FrequencyComparison {
+            interest_saved: self.total_interest_paid(&baseline) - parallel.total_interest_paid(&alternative),
+            payoff_date_difference: (payoff(&baseline) - payoff(&alternative)).num_days(),
+            periods: alternative.len() as u32,
+        }
+    }
+
+    /// Refinances once `at_period` is paid: the balance left moves to a new loan
+    /// at `new_rate` over `new_term` periods, starting on the next payment date
+    /// with the same method, frequency, day count, rounding and currency scale.
+    /// Returns the new loan's schedule and the stay-versus-refinance comparison.
+    pub fn refinance(
+        &self,
+        at_period: impl Into<Period>,
+        new_rate: Decimal,
+        new_term: u32,
+        fees: impl Into<Money>,
+        schedule: &[PaymentSchedule],
+    ) -> Result<(Vec<PaymentSchedule>, RefinanceSummary), BuildError> {
+        let Period(at_period) = at_period.into();
+        let Money(fees) = fees.into();
+
+        let row = self.row_for_period(schedule, at_period).ok_or(BuildError::Invalid {
+            field: "at_period",
+            reason: "must be a period in the schedule",
+        })?;
+        let balance = row.remaining_principal - row.principal_payment;
+        let next_payment_date = self.scheduled_payment_date(at_period - self.done_months);
+
+        let mut new_loan = Loan::new(balance, new_rate, 0, new_term, next_payment_date, self.method)?;
+        new_loan.payment_day = self.payment_day;
+        new_loan.frequency = self.frequency;
+        new_loan.day_count = self.day_count;
+        new_loan.rounding = self.rounding;
+        new_loan.currency_scale = self.currency_scale;
+        new_loan.monthly_principal_payment = new_loan.round(balance / Decimal::from(new_term));
+
+        let new_schedule = new_loan.generate_schedule();
+        let interest_if_staying = schedule.iter().filter(|p| p.period > at_period).map(|p| p.interest).sum();
+        let interest_if_refinancing = new_loan.total_interest_paid(&new_schedule);
+
+        let summary = RefinanceSummary {
+            balance_transferred: balance,
+            interest_if_staying,
+            interest_if_refinancing,
+            fees,
+            net_savings: interest_if_staying - interest_if_refinancing - fees,
+        };
+
+        Ok((new_schedule, summary))
+    }
+
+    /// Months after `at_period` until the interest saved by refinancing, month
+    /// against month, exceeds `fees`. `None` if it never does or the refinance
+    /// itself is invalid.
+    pub fn refinance_breakeven(
+        &self,
+        at_period: impl Into<Period>,
+        new_rate: Decimal,
+        new_term: u32,
+        fees: impl Into<Money>,
+        schedule: &[PaymentSchedule],
+    ) -> Option<u32> {
+        let Period(at_period) = at_period.into();
+        let Money(fees) = fees.into();
+
+        let (new_schedule, _) = self.refinance(at_period, new_rate, new_term, fees, schedule).ok()?;
+        let mut staying = schedule.iter().filter(|p| p.period > at_period).map(|p| p.interest);
+        let mut saved = Decimal::ZERO;
+
+        for (month, row) in (1..).zip(&new_schedule) {
+            saved += staying.next().unwrap_or(Decimal::ZERO) - row.interest;
+            if saved > fees {
+                return Some(month);
+            }
+        }
+        None
+    }
+
+    /// Prepayment penalties, kept apart from `total_interest_paid`.
+    pub fn total_fees(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule.iter().filter_map(|p| p.penalty).sum()
+    }
+
+    /// Principal repaid, including early payments.
+    pub fn total_principal_paid(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule.iter().map(|p| p.principal_payment).sum::<Decimal>() + self.total_early_payments(schedule)
+    }
+
+    /// All cash paid: regular payments, early payments and fees.
+    pub fn total_paid(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule.iter().map(|p| p.total_payment).sum::<Decimal>()
+            + self.total_early_payments(schedule)
+            + self.total_fees(schedule)
+    }
+
+    /// Everything the loan costs from `start_date` on; the same figure as
+    /// `total_paid`, named for comparing offers.
+    pub fn total_cost(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        self.total_paid(schedule)
+    }
+
+    /// `total_cost` less the principal borrowed: interest, fees and penalties.
+    pub fn cost_of_credit(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        self.total_cost(schedule) - self.principal
+    }
+
+    pub fn total_early_payments(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule.iter().filter_map(|p| p.early_payment).sum()
+    }
+
+    /// The rows that carry an early payment, in schedule order.
+    pub fn early_payment_rows<'a>(&self, schedule: &'a [PaymentSchedule]) -> Vec<&'a PaymentSchedule> {
+        schedule.iter().filter(|p| p.early_payment.is_some()).collect()
+    }
+
+    pub fn early_payment_count(&self, schedule: &[PaymentSchedule]) -> usize {
+        schedule.iter().filter(|p| p.early_payment.is_some()).count()
+    }
+
+    /// `(period, interest saved)` for every early payment in `schedule`.
+    ///
+    /// Each saving is measured against the schedule as it stood right before
+    /// that payment, so the baseline is simply the schedule from
+    /// `generate_schedule` (plus any rate changes) before the first prepayment.
+    /// Applied in order, the savings add up to the total saved versus that
+    /// baseline, as long as no rate change is applied between prepayments.
+    pub fn early_payment_savings(&self, schedule: &[PaymentSchedule]) -> Vec<(u32, Decimal)> {
+        schedule
+            .iter()
+            .filter_map(|p| p.interest_saved.map(|saved| (p.period, saved)))
+            .collect()
+    }
+
+    /// Principal the schedule leaves unpaid (or, if negative, overpays) through
+    /// per-row rounding: the last row's closing balance. Zero when it ties out.
+    pub fn rounding_residual(&self, schedule: &[PaymentSchedule]) -> Decimal {
+        schedule
+            .last()
+            .map_or(Decimal::ZERO, |p| p.remaining_principal - p.principal_payment)
+    }
+
+    /// Folds `rounding_residual` into the last row's principal so the principal
+    /// paid ties out to the balance exactly.
+    pub fn reconcile(&mut self, schedule: &mut [PaymentSchedule]) {
+        let residual = self.rounding_residual(schedule);
+        let Some(last) = schedule.last_mut() else {
+            return;
+        };
+
+        last.principal_payment += residual;
+        last.total_payment = self.total_for(last);
+        update_cumulative_totals(schedule);
+    }
+
+    /// The row for absolute `period` (counting `done_months`), or `None` if the
+    /// schedule no longer reaches it, e.g. after the term was shortened.
+    pub fn row_for_period<'a>(
+        &self,
+        schedule: &'a [PaymentSchedule],
+        period: impl Into<Period>,
+    ) -> Option<&'a PaymentSchedule> {
+        let Period(period) = period.into();
+
+        let idx = period.checked_sub(self.done_months + 1)? as usize;
+        schedule
+            .get(idx)
+            .filter(|p| p.period == period)
+            .or_else(|| schedule.iter().find(|p| p.period == period))
+    }
+
+    /// Each `payment_date` mapped to the `total_payment` due then, in date order.
+    /// Rows sharing a date are summed.
+    pub fn payment_calendar(&self, schedule: &[PaymentSchedule]) -> BTreeMap<NaiveDate, Decimal> {
+        let mut calendar = BTreeMap::new();
+        for p in schedule {
+            *calendar.entry(p.payment_date).or_insert(Decimal::ZERO) += p.total_payment;
+        }
+        calendar
+    }
+
+    pub fn series(&self, schedule: &[PaymentSchedule]) -> ChartSeries {
+        let mut series = ChartSeries::default();
+
+        for p in schedule {
+            series.periods.push(p.period);
+            series.dates.push(p.payment_date);
+            series.interest.push(p.interest);
+            series.principal.push(p.principal_payment + p.early_payment.unwrap_or_default());
+            series.balance.push(p.remaining_principal - p.principal_payment);
+            series.cumulative_interest.push(p.cumulative_interest);
+        }
+
+        series
+    }
+
+    /// Interest, principal and payments per calendar year, in schedule order.
+    pub fn yearly_summary(&self, schedule: &[PaymentSchedule]) -> Vec<YearSummary> {
+        let mut years: Vec<YearSummary> = Vec::new();
+
+        for p in schedule {
+            let year = p.payment_date.year();
+            if years.last().map(|y| y.year) != Some(year) {
+                years.push(YearSummary {
+                    year,
+                    interest: Decimal::ZERO,
+                    principal: Decimal::ZERO,
+                    payments: Decimal::ZERO,
+                    ending_balance: Decimal::ZERO,
+                });
+            }
+
+            let early = p.early_payment.unwrap_or_default();
+            let summary = years.last_mut().expect("pushed above");
+            summary.interest += p.interest;
+            summary.principal += p.principal_payment + early;
+            summary.payments += p.total_payment + early + p.penalty.unwrap_or_default();
+            summary.ending_balance = p.remaining_principal - p.principal_payment;
+        }
+
+        years
+    }
+
+    /// Interest as a percentage of the row's `total_payment` at `period`, to
+    /// two decimal places. Zero for a period not in `schedule` or a row with
+    /// nothing due.
+    pub fn interest_share(&self, period: impl Into<Period>, schedule: &[PaymentSchedule]) -> Decimal {
+        let Period(period) = period.into();
+
+        self.row_for_period(schedule, period)
+            .map_or(Decimal::ZERO, |p| self.share_of_payment(p))
+    }
+
+    /// `(period, interest share)` for every row, as in `interest_share`.
+    pub fn interest_shares(&self, schedule: &[PaymentSchedule]) -> Vec<(u32, Decimal)> {
+        schedule.iter().map(|p| (p.period, self.share_of_payment(p))).collect()
+    }
+
+    fn share_of_payment(&self, payment: &PaymentSchedule) -> Decimal {
+        if payment.total_payment.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        (payment.interest / payment.total_payment * Decimal::ONE_HUNDRED).round_dp(2)
+    }
+
+    /// Balance left after the last payment due on or before `date`: the
+    /// starting principal before the first payment, zero once paid off.
+    pub fn balance_on(&self, schedule: &[PaymentSchedule], date: NaiveDate) -> Decimal {
+        schedule
+            .iter()
+            .take_while(|p| p.payment_date <= date)
+            .last()
+            .map_or(self.principal, |p| p.remaining_principal - p.principal_payment)
+    }
+
+    /// What settling the loan on `date` costs: `balance_on(date)` plus interest
+    /// accrued on it, daily on the loan's day-count basis, since the last
+    /// payment date (or the start of the first period). A payment due on `date`
+    /// counts as already made; zero once the schedule has run out.
+    pub fn payoff_amount_on(&self, schedule: &[PaymentSchedule], date: NaiveDate) -> Decimal {
+        let Some(next) = schedule.iter().find(|p| p.payment_date > date) else {
+            return Decimal::ZERO;
+        };
+
+        let since = schedule
+            .iter()
+            .take_while(|p| p.payment_date <= date)
+            .last()
+            .map_or_else(
+                || {
+                    let days = Days::new(self.accrual_days(next.period).max(0) as u64);
+                    next.payment_date.checked_sub_days(days).unwrap_or(NaiveDate::MIN)
+                },
+                |p| p.payment_date,
+            );
+        let days = (date - since).num_days().max(0);
+        let year_days = match self.day_count {
+            DayCount::Actual365 => 365,
+            DayCount::Thirty360 | DayCount::Chinese30_360 => 360,
+        };
+
+        let balance = self.balance_on(schedule, date);
+        let accruing = (balance - self.offset_on(date)).max(Decimal::ZERO);
+        let rate = self.rate_for_period(next.period, next.payment_date);
+        let interest = accruing * rate / Decimal::ONE_HUNDRED * Decimal::from(days) / Decimal::from(year_days);
+
+        balance + self.round(interest)
+    }
+
+    /// Marks every row due on or before `date` as `Paid`. Rows already paid stay
+    /// paid, so history cannot be un-frozen by marking an earlier date.
+    pub fn mark_paid_through(&self, date: NaiveDate, schedule: &mut [PaymentSchedule]) {
+        for payment in schedule.iter_mut().filter(|p| p.payment_date <= date) {
+            payment.status = PaymentStatus::Paid;
+        }
+    }
+
+    /// Percentage of `principal` repaid as of `as_of`, to two decimal places:
+    /// 0 before the first payment, 100 once the loan is paid off.
+    pub fn payoff_progress(&self, schedule: &[PaymentSchedule], as_of: NaiveDate) -> Decimal {
+        if self.principal <= Decimal::ZERO {
+            return Decimal::ONE_HUNDRED;
+        }
+
+        let repaid = (self.principal - self.balance_on(schedule, as_of)).max(Decimal::ZERO);
+        (repaid / self.principal * Decimal::ONE_HUNDRED).round_dp(2).min(Decimal::ONE_HUNDRED)
+    }
+
+    pub fn summary(&self, schedule: &[PaymentSchedule]) -> LoanSummary {
+        LoanSummary {
+            total_interest: self.total_interest_paid(schedule),
+            total_principal: self.total_principal_paid(schedule),
+            total_paid: self.total_paid(schedule),
+            total_early_payments: self.total_early_payments(schedule),
+            total_fees: self.total_fees(schedule),
+            number_of_payments: schedule.len(),
+            contractual_payments: self.remaining_months() as usize,
+            months_saved: (self.remaining_months() as usize).saturating_sub(schedule.len()),
+            payoff_date: schedule.last().map(|p| p.payment_date),
+        }
+    }
+
+    /// Stress test: the summary of `schedule` if the rate in force at
+    /// `from_period` jumped by `bps` basis points from then on. Works on copies,
+    /// so neither the loan nor `schedule` changes.
+    pub fn rate_shock(
+        &self,
+        bps: i32,
+        from_period: impl Into<Period>,
+        schedule: &[PaymentSchedule],
+    ) -> Result<LoanSummary, RateChangeError> {
+        let Period(from_period) = from_period.into();
+
+        let current = self
+            .row_for_period(schedule, from_period)
+            .map_or(self.annual_rate, |p| p.interest_rate);
+        let shocked = current + Decimal::new(bps.into(), 2);
+
+        let mut loan = self.clone();
+        let mut rows = schedule.to_vec();
+        loan.adjust_rate(shocked, from_period, &mut rows)?;
+        Ok(loan.summary(&rows))
+    }
+
+    /// Annual percentage rate of the actual cash flows: the opening balance less
+    /// `upfront_fees` received one period before the first row, then every
+    /// row's payment, early payment and penalty. Solved as an internal rate of
+    /// return by bisection and annualized nominally like `annual_rate`, in
+    /// percent to four decimal places.
+    pub fn apr(&self, schedule: &[PaymentSchedule], upfront_fees: impl Into<Money>) -> Decimal {
+        let Money(upfront_fees) = upfront_fees.into();
+
+        let Some(first) = schedule.first() else {
+            return Decimal::ZERO;
+        };
+
+        let disbursed = (first.remaining_principal - upfront_fees).to_f64().unwrap_or(0.0);
+        let payments: Vec<f64> = schedule
+            .iter()
+            .map(|p| {
+                let paid = p.total_payment + p.early_payment.unwrap_or_default() + p.penalty.unwrap_or_default();
+                paid.to_f64().unwrap_or(0.0)
+            })
+            .collect();
+
+        // Present value of the payments less the disbursement; it falls as the
+        // rate rises, so bisect for its root.
+        let npv = |rate: f64| {
+            let mut discount = 1.0;
+            let mut pv = -disbursed;
+            for paid in &payments {
+                discount /= 1.0 + rate;
+                pv += paid * discount;
+            }
+            pv
+        };
+
+        let (mut low, mut high) = (-0.99, 1.0);
+        for _ in 0..200 {
+            let mid = (low + high) / 2.0;
+            if npv(mid) > 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        let annual = (low + high) / 2.0 * f64::from(self.frequency.periods_per_year()) * 100.0;
+        Decimal::from_f64(annual).unwrap_or_default().round_dp(4)
+    }
+}
+
+/// `(period, new rate)` for every row whose `interest_rate` differs from the
+/// row before it. The first row is not a change.
+pub fn rate_change_periods(schedule: &[PaymentSchedule]) -> Vec<(u32, Decimal)> {
+    schedule
+        .windows(2)
+        .filter(|pair| pair[0].interest_rate != pair[1].interest_rate)
+        .map(|pair| (pair[1].period, pair[1].interest_rate))
+        .collect()
+}
+
+/// Consolidates two schedules by `payment_date`, summing the amounts due on
+/// each date. A date either loan pays on gets a row. Both schedules must be in
+/// date order, as generated.
+pub fn merge_schedules(a: &[PaymentSchedule], b: &[PaymentSchedule]) -> Vec<CombinedRow> {
+    let mut merged = Vec::with_capacity(a.len().max(b.len()));
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    let (mut balance_a, mut balance_b) = (None, None);
+
+    loop {
+        let payment_date = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => x.payment_date.min(y.payment_date),
+            (Some(x), None) => x.payment_date,
+            (None, Some(y)) => y.payment_date,
+            (None, None) => break,
+        };
+
+        let mut row = CombinedRow {
+            payment_date,
+            interest: Decimal::ZERO,
+            principal_payment: Decimal::ZERO,
+            total_payment: Decimal::ZERO,
+            remaining_principal_a: None,
+            remaining_principal_b: None,
+        };
+
+        for (rows, balance) in [(&mut a, &mut balance_a), (&mut b, &mut balance_b)] {
+            if let Some(p) = rows.next_if(|p| p.payment_date == payment_date) {
+                row.interest += p.interest;
+                row.principal_payment += p.principal_payment;
+                row.total_payment += p.total_payment;
+                *balance = Some(p.remaining_principal - p.principal_payment);
+            }
+        }
+        row.remaining_principal_a = balance_a;
+        row.remaining_principal_b = balance_b;
+
+        merged.push(row);
+    }
+
+    merged
+}
+
+// Whether any row from absolute `period` on is frozen as paid.
+fn is_paid_from(schedule: &[PaymentSchedule], period: u32) -> bool {
+    schedule
+        .iter()
+        .any(|p| p.period >= period && p.status == PaymentStatus::Paid)
+}
+
+fn update_cumulative_totals(schedule: &mut [PaymentSchedule]) {
+    let mut interest = Decimal::ZERO;
+    let mut principal = Decimal::ZERO;
+
+    for payment in schedule {
+        interest += payment.interest;
+        principal += payment.principal_payment + payment.early_payment.unwrap_or_default();
+        payment.cumulative_interest = interest;
+        payment.cumulative_principal = principal;
+    }
+}
+
+// (P * (1 + r)^n - B) * r / ((1 + r)^n - 1), leaving a balance of `balloon`
+// after the last payment; falls back to (P - B) / n for a zero rate. Left
+// unrounded so the caller applies the loan's rounding strategy.
+fn annuity_payment(principal: Decimal, rate: Decimal, periods: u32, balloon: Decimal) -> Decimal {
+    if rate.is_zero() {
+        return (principal - balloon) / Decimal::from(periods);
+    }
+
+    // A rate high enough to overflow (1 + r)^n, e.g. a rate change to 500% on a
+    // long term, makes the payment indistinguishable from its limit P * r.
+    let payment = (0..periods)
+        .try_fold(Decimal::ONE, |growth, _| growth.checked_mul(Decimal::ONE + rate))
+        .and_then(|growth| {
+            let owed = principal.checked_mul(growth)? - balloon;
+            Some(owed.checked_mul(rate)? / (growth - Decimal::ONE))
+        });
+
+    payment.unwrap_or(principal * rate)
+}
+
+/// Monthly rate as a fraction for a percentage `annual_rate`, e.g. 4.2 -> 0.0035.
+pub fn monthly_rate(annual_rate: Decimal) -> Decimal {
+    rate_per_period(annual_rate, 12)
+}
+
+// The single place a percentage annual rate becomes a per-period fraction, so
+// every code path divides in the same order.
+fn rate_per_period(annual_rate: Decimal, periods_per_year: u32) -> Decimal {
+    annual_rate / Decimal::from(periods_per_year) / Decimal::from(100)
+}
+
+/// Steps forward whole months, clamping the day to the end of shorter months
+/// (Jan 31 -> Feb 28/29). Always step from the original anchor date so a
+/// clamped month does not drag the day down for the rest of the schedule.
+/// Saturates at `NaiveDate::MAX` rather than panicking.
+pub fn add_months(date: NaiveDate, months: u32) -> NaiveDate {
+    date.checked_add_months(Months::new(months)).unwrap_or(NaiveDate::MAX)
+}
+
+/// One month after `date`. Chaining it drifts once a day is clamped
+/// (2024-02-29 -> 2025-02-28 -> 2025-03-28); schedules instead step from the
+/// start date with `add_months` and restore `payment_day`, so a 29th anchor
+/// falls on Feb 28 in common years and Feb 29 again in leap years.
+pub fn next_month(date: NaiveDate) -> NaiveDate {
+    add_months(date, 1)
+}
+
+// `day` is clamped into the month, so a 0 or 31 never fails.
+fn with_day_clamped(date: NaiveDate, day: u32) -> NaiveDate {
+    let first = date - Days::new(date.day0() as u64);
+    let last_day = (add_months(first, 1) - first).num_days().max(1) as u32;
+
+    first + Days::new((day.clamp(1, last_day) - 1) as u64)
+}
+
+fn default_rounding() -> RoundingStrategy {
+    RoundingStrategy::MidpointNearestEven
+}
+
+fn default_currency_scale() -> u32 {
+    2
+}
+
+// `RoundingStrategy` has no serde support of its own; store it by variant name.
+pub(crate) mod rounding_strategy {
+    use rust_decimal::RoundingStrategy;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    #[allow(deprecated)]
+    pub fn serialize<S: Serializer>(strategy: &RoundingStrategy, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match strategy {
+            RoundingStrategy::MidpointNearestEven | RoundingStrategy::BankersRounding => "MidpointNearestEven",
+            RoundingStrategy::MidpointAwayFromZero | RoundingStrategy::RoundHalfUp => "MidpointAwayFromZero",
+            RoundingStrategy::MidpointTowardZero | RoundingStrategy::RoundHalfDown => "MidpointTowardZero",
+            RoundingStrategy::ToZero | RoundingStrategy::RoundDown => "ToZero",
+            RoundingStrategy::AwayFromZero | RoundingStrategy::RoundUp => "AwayFromZero",
+            RoundingStrategy::ToNegativeInfinity => "ToNegativeInfinity",
+            RoundingStrategy::ToPositiveInfinity => "ToPositiveInfinity",
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RoundingStrategy, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        from_name(&name)
+    }
+
+    pub fn deserialize_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<RoundingStrategy>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|name| from_name(&name))
+            .transpose()
+    }
+
+    const VARIANTS: &[&str] = &[
+        "MidpointNearestEven",
+        "MidpointAwayFromZero",
+        "MidpointTowardZero",
+        "ToZero",
+        "AwayFromZero",
+        "ToNegativeInfinity",
+        "ToPositiveInfinity",
+    ];
+
+    fn from_name<E: de::Error>(name: &str) -> Result<RoundingStrategy, E> {
+        match name {
+            "MidpointNearestEven" => Ok(RoundingStrategy::MidpointNearestEven),
+            "MidpointAwayFromZero" => Ok(RoundingStrategy::MidpointAwayFromZero),
+            "MidpointTowardZero" => Ok(RoundingStrategy::MidpointTowardZero),
+            "ToZero" => Ok(RoundingStrategy::ToZero),
+            "AwayFromZero" => Ok(RoundingStrategy::AwayFromZero),
+            "ToNegativeInfinity" => Ok(RoundingStrategy::ToNegativeInfinity),
+            "ToPositiveInfinity" => Ok(RoundingStrategy::ToPositiveInfinity),
+            _ => Err(E::unknown_variant(name, VARIANTS)),
+        }
+    }
+}
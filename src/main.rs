@@ -1,289 +1,175 @@
-use chrono::{Datelike, NaiveDate};
-use rust_decimal::prelude::FromStr;
-use rust_decimal::Decimal;
-
-#[derive(Debug, Clone, Copy)]
-struct Loan {
-    principal: Decimal,
-    annual_rate: Decimal,
-    done_months: u32,
-    months: u32,
-    start_date: NaiveDate,
-    monthly_principal_payment: Decimal,
-}
-
-#[derive(Debug, Clone)]
-struct PaymentSchedule {
-    period: u32,
-    interest: Decimal,
-    principal_payment: Decimal,
-    remaining_principal: Decimal,
-    total_payment: Decimal,
-    interest_rate: Decimal,
-    payment_date: NaiveDate,
-    early_payment: Option<Decimal>,
-}
-
-impl Loan {
-    fn new(
-        principal: Decimal,
-        annual_rate: Decimal,
-        done_months: u32,
-        months: u32,
-        start_date: NaiveDate,
-    ) -> Self {
-        let monthly_principal_payment = (principal / Decimal::from(months - done_months)).round_dp(2);
-        Self {
-            principal,
-            annual_rate,
-            done_months,
-            months,
-            start_date,
-            monthly_principal_payment,
-        }
-    }
-
-    fn generate_schedule(&self) -> Vec<PaymentSchedule> {
-        let mut schedule = Vec::new();
-        let months = self.months - self.done_months;
-        let monthly_principal_payment = self.monthly_principal_payment;
-        let mut remaining_principal = self.principal;
-        let monthly_rate = self.annual_rate / Decimal::from(12) / Decimal::from(100);
-        let mut current_date = self.start_date;
-
-        for period in 1..=months {
-            let interest = (remaining_principal * monthly_rate).round_dp(2);
-            let total_payment = (monthly_principal_payment + interest).round_dp(2);
-
-            let monthly_principal_payment = if remaining_principal < monthly_principal_payment {
-                remaining_principal
-            } else {
-                monthly_principal_payment
-            };
+mod cli;
 
-            schedule.push(PaymentSchedule {
-                period: period + self.done_months,
-                interest,
-                principal_payment: monthly_principal_payment,
-                remaining_principal: remaining_principal,
-                total_payment,
-                interest_rate: self.annual_rate,
-                payment_date: current_date,
-                early_payment: None,
-            });
+use std::env;
+use std::io;
+use std::process;
 
-            remaining_principal -= monthly_principal_payment;
+use chrono::NaiveDate;
+use prepayment::{
+    print_schedule, schedule_to_csv, schedule_to_ics, write_schedule_jsonl, Loan, LoanBuilder, Locale, PaymentSchedule,
+    PrepaymentStrategy, RepaymentMethod,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 
-            current_date = current_date
-                .with_month((current_date.month0() + 1) % 12 + 1)
-                .and_then(|date| {
-                    date.with_year(current_date.year() + (current_date.month0() + 1) as i32 / 12)
-                })
-                .expect("Failed to calculate date");
-        }
+use cli::{Cli, CliError, Format, HELP, USAGE};
 
-        schedule
-    }
+// Reduce-monthly prepayments re-amortize the rows from that period on, so the
+// current installment is read off the schedule rather than the loan.
+fn installment_at(loan: &Loan, schedule: &[PaymentSchedule], period: u32) -> Decimal {
+    loan.row_for_period(schedule, period)
+        .map_or(loan.monthly_principal_payment, |p| p.principal_payment)
+}
 
-    fn adjust_rate(
-        &mut self,
-        new_rate: Decimal,
-        from_period: u32,
-        schedule: &mut Vec<PaymentSchedule>,
-    ) {
-        self.annual_rate = new_rate;
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
 
-        for payment in schedule.iter_mut().skip((from_period - 1) as usize) {
-            payment.interest_rate = new_rate;
-            let monthly_rate = new_rate / Decimal::from(12) / Decimal::from(100);
-            payment.interest = (payment.remaining_principal * monthly_rate).round_dp(2);
-            payment.total_payment = payment.principal_payment + payment.interest;
-        }
+    // Without options, run the built-in example scenario.
+    if args.is_empty() {
+        run_example();
+        return;
     }
 
-    fn make_early_payment(
-        &mut self,
-        extra_payment: Decimal,
-        period: u32,
-        shorten_term: bool,
-        schedule: &mut Vec<PaymentSchedule>,
-    ) {
-        let mut idx: u32 = period - self.done_months - 1;
-        if idx < 0 as u32 {
+    let cli = match Cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(CliError::Help) => {
+            println!("{}\n\n{}", USAGE, HELP);
             return;
         }
-
-        if idx as usize >= schedule.len() {
-            return;
-        }
-
-        let mut remaining_principal = (schedule[idx as usize].remaining_principal - extra_payment).round_dp(2);
-
-        if remaining_principal < Decimal::from(0) {
-            return;
-        }
-
-        schedule[idx as usize].early_payment = Some(extra_payment);
-
-        if shorten_term {
-            for payment in &mut schedule[idx as usize..] {
-                let monthly_rate = payment.interest_rate / Decimal::from(12) / Decimal::from(100);
-                let interest = (remaining_principal * monthly_rate).round_dp(2);
-
-                payment.remaining_principal = remaining_principal;
-                payment.principal_payment = if remaining_principal < payment.principal_payment {
-                    remaining_principal
-                } else {
-                    payment.principal_payment
-                };
-                payment.interest = interest;
-                payment.total_payment = (payment.principal_payment + interest).round_dp(2);
-    
-                remaining_principal -= payment.principal_payment;
-
-                idx += 1;
-
-                if remaining_principal.is_zero() {
-                    schedule.truncate(idx as usize);
-                    break;
-                }
-            }
+        Err(err) => {
+            eprintln!("error: {}\n{}", err, USAGE);
+            process::exit(2);
         }
+    };
 
-        if !shorten_term {
-            let remaining_period = self.months - schedule[idx as usize].period + 1;
-            
-            self.monthly_principal_payment = (remaining_principal / Decimal::from(remaining_period)).round_dp(2);
-
-            for payment in &mut schedule[idx as usize..] {
-                let monthly_rate = payment.interest_rate / Decimal::from(12) / Decimal::from(100);
-                let interest = (remaining_principal * monthly_rate).round_dp(2);
+    if let Err(err) = run(&cli) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
 
-                payment.remaining_principal = remaining_principal;
-                payment.principal_payment = self.monthly_principal_payment;
-                payment.principal_payment = if remaining_principal < payment.principal_payment {
-                    remaining_principal
-                } else {
-                    payment.principal_payment
-                };
-                payment.interest = interest;
-                payment.total_payment = (payment.principal_payment + interest).round_dp(2);
-    
-                remaining_principal -= payment.principal_payment;
+fn run(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let mut loan = LoanBuilder::new()
+        .principal(cli.principal)
+        .annual_rate(cli.rate)
+        .done_months(cli.done_months)
+        .total_months(cli.months)
+        .start_date(cli.start_date)
+        .method(cli.method)
+        .build()?;
 
-                idx += 1;
-            }
-        }
+    let mut schedule = loan.generate_schedule();
+    let early_payments: Vec<_> = cli
+        .early_payments
+        .iter()
+        .map(|p| (p.period, p.amount, p.strategy))
+        .collect();
+    loan.apply_early_payments(&early_payments, &mut schedule)?;
+    for warning in loan.warnings(&schedule) {
+        eprintln!("warning: {}", warning);
     }
 
-    fn total_interest_paid(&self, schedule: &Vec<PaymentSchedule>) -> Decimal {
-        schedule.iter().map(|p| p.interest).sum()
+    match cli.format {
+        Format::Table => print_schedule(&schedule, Locale::En),
+        Format::Csv => print!("{}", schedule_to_csv(&schedule, Locale::En, loan.currency_scale)),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&schedule)?),
+        Format::JsonLines => write_schedule_jsonl(&schedule, &mut io::stdout().lock())?,
+        Format::Ics => print!("{}", schedule_to_ics(&schedule, "Loan payment", loan.currency_scale)),
     }
 
-    // fn find_remaining_schedule<'a>(
-    //     &self,
-    //     schedule: &'a mut Vec<PaymentSchedule>,
-    //     period: u32,
-    // ) -> &'a mut [PaymentSchedule] {
-    //     &mut schedule[period as usize - 1..]
-    // }
+    Ok(())
 }
 
-fn main() {
+fn run_example() {
     let start_date = NaiveDate::from_ymd_opt(2024, 10, 19).expect("Invalid date provided");
 
-    let loan = Loan::new(
-        Decimal::from_str("536714.20").unwrap(),
-        Decimal::from_str("4.2").unwrap(),
-        57,
-        288,
-        start_date,
-    );
-
-    let loan2 = Loan::new(
-        Decimal::from_str("536714.20").unwrap(),
-        Decimal::from_str("4.2").unwrap(),
-        57,
-        288,
-        start_date,
-    );
-
-    let mut schedule = loan.generate_schedule();
-
-    let mut schedule2 = loan2.generate_schedule();
-
-    // Example: Adjust rate at a certain period
-    let mut loan_clone = loan.clone();
-    let mut loan_clone2 = loan2.clone();
-
-    loan_clone.adjust_rate(Decimal::from_str("3.9").unwrap(), 2, &mut schedule);
-    loan_clone.adjust_rate(Decimal::from_str("3.55").unwrap(), 3, &mut schedule);
-    
-    loan_clone2.adjust_rate(Decimal::from_str("3.9").unwrap(), 2, &mut schedule2);
-    loan_clone2.adjust_rate(Decimal::from_str("3.55").unwrap(), 3, &mut schedule2);
-
-    loan_clone.make_early_payment(
-        (loan_clone.monthly_principal_payment * Decimal::from(43)).round_dp(2),
-        58,
-        true,
-        &mut schedule
-    );
-    
-    loan_clone2.make_early_payment(
-        (loan_clone2.monthly_principal_payment * Decimal::from(43)).round_dp(2),
-        58,
-        true,
-        &mut schedule2
-    );
+    // The rate drops to 3.9% at period 59 and 3.55% at period 60.
+    let loan = LoanBuilder::new()
+        .principal(dec!(536714.20))
+        .annual_rate(dec!(4.2))
+        .rate_schedule(vec![
+            (59, dec!(3.9)),
+            (60, dec!(3.55)),
+        ])
+        .done_months(57)
+        .total_months(288)
+        .start_date(start_date)
+        .method(RepaymentMethod::EqualPrincipal)
+        .build()
+        .expect("Invalid loan parameters");
+
+    let baseline = loan.generate_schedule();
+    let mut loan_clone = loan;
+
+    let mut schedule = loan_clone
+        .with_early_payment(
+            (loan_clone.monthly_principal_payment * Decimal::from(43)).round_dp(2),
+            58,
+            PrepaymentStrategy::ShortenTerm,
+            &baseline,
+        )
+        .expect("Initial early payment should fit the balance");
+    let (mut loan_clone2, mut schedule2) = loan_clone.fork(&schedule);
 
     for period in 0..=schedule.len() {
         if (59 + period) % 3 == 0 {
-            let payment = (Decimal::from(10000) / loan_clone.monthly_principal_payment).trunc() * loan_clone.monthly_principal_payment;
-            let payment2 = (Decimal::from(10000) / loan_clone2.monthly_principal_payment).trunc() * loan_clone2.monthly_principal_payment;
+            let installment2 = installment_at(&loan_clone2, &schedule2, 59 + period as u32);
+            let payment2 = (Decimal::from(10000) / installment2).trunc() * installment2;
 
-            loan_clone.make_early_payment(
-                payment,
-                59 + period as u32,
-                true,
-                &mut schedule
-            );
-
-            loan_clone2.make_early_payment(
+            // Payments past the (shortened) payoff are rejected, which is fine here.
+            let _ = loan_clone2.make_early_payment(
                 payment2,
                 59 + period as u32,
-                false,
+                PrepaymentStrategy::ReduceInstallment,
                 &mut schedule2
             );
         }
 
         if period > 12 && period % 12 == 0 {
-            let payment2 = (Decimal::from(10000) / loan_clone2.monthly_principal_payment).trunc() * loan_clone2.monthly_principal_payment;
-            loan_clone2.make_early_payment(
+            let installment2 = installment_at(&loan_clone2, &schedule2, 59 + period as u32);
+            let payment2 = (Decimal::from(10000) / installment2).trunc() * installment2;
+            let _ = loan_clone2.make_early_payment(
                 payment2,
                 59 + period as u32,
-                false,
+                PrepaymentStrategy::ReduceInstallment,
                 &mut schedule2
             );
         }
     }
 
-    println!("缩短期限 {}", loan_clone.total_interest_paid(&schedule));
-    println!("减少月供 {}", loan_clone2.total_interest_paid(&schedule2));
+    let payment = (Decimal::from(10000) / loan_clone.monthly_principal_payment).trunc() * loan_clone.monthly_principal_payment;
+    loan_clone
+        .make_recurring_early_payment(payment, 3, 60, PrepaymentStrategy::ShortenTerm, &mut schedule)
+        .expect("Recurring early payments should start inside the schedule");
 
-    println!("Period\tRemaining Balance\tMonth\tRate\tInterest\tPrincipal\tPayment\t\tEarly Payment");
-    println!("-----------------------------------------------------------");
-    for p in &schedule2 {
-        println!(
-            "{}\t{:<8}\t{}\t{}\t{:<8}\t{:<8}\t{:<8}\t{:<8}",
-            p.period,
-            p.remaining_principal,
-            p.payment_date,
-            p.interest_rate,
-            p.interest,
-            p.principal_payment,
-            p.total_payment,
-            p.early_payment
-                .map_or_else(|| "None".to_string(), |v| v.to_string()),
-        );
-    }
+    let labels = Locale::ZhCn.labels();
+
+    println!(
+        "{} {} {} {}",
+        labels.shorten_term,
+        loan_clone.total_interest_paid(&schedule),
+        labels.interest_saved,
+        loan_clone.total_interest_saved(&baseline, &schedule),
+    );
+    println!(
+        "{} {} {} {}",
+        labels.reduce_installment,
+        loan_clone2.total_interest_paid(&schedule2),
+        labels.interest_saved,
+        loan_clone2.total_interest_saved(&baseline, &schedule2),
+    );
+
+    let installment_loan = LoanBuilder::new()
+        .principal(dec!(536714.20))
+        .annual_rate(dec!(3.55))
+        .done_months(57)
+        .total_months(288)
+        .start_date(start_date)
+        .method(RepaymentMethod::EqualInstallment)
+        .build()
+        .expect("Invalid loan parameters");
+    let installment_schedule = installment_loan.generate_schedule();
+    println!("{} {}", labels.equal_installment, installment_loan.total_interest_paid(&installment_schedule));
+
+    print_schedule(&schedule2, Locale::ZhCn);
 }
@@ -0,0 +1,350 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::{BuildError, EarlyPaymentError, Loan, LoanBuilder, PaymentSchedule, PrepaymentStrategy, RateChangeError};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RateChange {
+    pub from_period: u32,
+    pub rate: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EarlyPayment {
+    pub period: u32,
+    pub amount: Decimal,
+    pub strategy: PrepaymentStrategy,
+}
+
+/// A loan plus the rate changes and prepayments to run against it. The `loan`
+/// table takes the same fields as `LoanBuilder` and is validated the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ScenarioFile")]
+pub struct Scenario {
+    pub loan: Loan,
+    pub rate_changes: Vec<RateChange>,
+    pub early_payments: Vec<EarlyPayment>,
+}
+
+#[derive(Deserialize)]
+struct ScenarioFile {
+    loan: LoanBuilder,
+    #[serde(default)]
+    rate_changes: Vec<RateChange>,
+    #[serde(default)]
+    early_payments: Vec<EarlyPayment>,
+}
+
+impl TryFrom<ScenarioFile> for Scenario {
+    type Error = BuildError;
+
+    fn try_from(file: ScenarioFile) -> Result<Self, Self::Error> {
+        Ok(Scenario {
+            loan: file.loan.build()?,
+            rate_changes: file.rate_changes,
+            early_payments: file.early_payments,
+        })
+    }
+}
+
+/// Where and why a scenario file that is valid JSON or YAML does not describe
+/// a valid scenario.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// 1-based line in the file: the offending field's, or the enclosing
+    /// table's when the field is missing.
+    pub line: usize,
+    /// The field at fault, when one can be named.
+    pub field: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "line {}: field `{}`: {}", self.line, field, self.reason),
+            None => write!(f, "line {}: {}", self.line, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    /// The file is not well-formed JSON.
+    Parse(serde_json::Error),
+    /// The file is not well-formed YAML.
+    ParseYaml(serde_yaml::Error),
+    Schema(SchemaError),
+    Build(BuildError),
+    RateChange(RateChangeError),
+    EarlyPayment(EarlyPaymentError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "failed to read scenario: {}", err),
+            ScenarioError::Parse(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::ParseYaml(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::Schema(err) => write!(f, "invalid scenario: {}", err),
+            ScenarioError::Build(err) => write!(f, "invalid scenario loan: {}", err),
+            ScenarioError::RateChange(err) => write!(f, "scenario rate change failed: {}", err),
+            ScenarioError::EarlyPayment(err) => write!(f, "scenario early payment failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScenarioError::Io(err) => Some(err),
+            ScenarioError::Parse(err) => Some(err),
+            ScenarioError::ParseYaml(err) => Some(err),
+            ScenarioError::Schema(err) => Some(err),
+            ScenarioError::Build(err) => Some(err),
+            ScenarioError::RateChange(err) => Some(err),
+            ScenarioError::EarlyPayment(err) => Some(err),
+        }
+    }
+}
+
+/// Reads a JSON scenario file. Malformed JSON is a `Parse` error; anything
+/// that does not describe a valid scenario, wrong field types and invalid
+/// loan terms included, is a `Schema` error naming the line and field.
+pub fn load_scenario(path: &Path) -> Result<Scenario, ScenarioError> {
+    let contents = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+    let raw: &RawValue = serde_json::from_str(&contents).map_err(ScenarioError::Parse)?;
+    validate(&contents, raw.get())
+}
+
+/// Reads a JSON file holding a top-level list of scenarios, each with its own
+/// loan, rate changes and prepayments, reporting errors as `load_scenario`
+/// does.
+pub fn load_scenarios(path: &Path) -> Result<Vec<Scenario>, ScenarioError> {
+    let contents = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+    let raw: Vec<&RawValue> = serde_json::from_str(&contents).map_err(ScenarioError::Parse)?;
+    raw.iter().map(|scenario| validate(&contents, scenario.get())).collect()
+}
+
+/// Reads a YAML scenario file: a single scenario, or a top-level list of them
+/// each with its own loan, rate changes and prepayments. Malformed YAML is a
+/// `ParseYaml` error; otherwise the checks and `Schema` errors are those of
+/// `load_scenario`.
+pub fn load_scenario_yaml(path: &Path) -> Result<Vec<Scenario>, ScenarioError> {
+    let contents = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+    let value: serde_yaml::Value = serde_yaml::from_str(&contents).map_err(ScenarioError::ParseYaml)?;
+
+    let files: Vec<ScenarioFile> = if value.is_sequence() {
+        serde_yaml::from_str(&contents)
+    } else {
+        serde_yaml::from_str(&contents).map(|file| vec![file])
+    }
+    .map_err(yaml_schema_error)?;
+
+    let mut items = yaml_items(&contents, value.is_sequence());
+    if items.len() != files.len() {
+        items = vec![0..contents.len(); files.len()];
+    }
+
+    let line_of = |at: usize| contents[..at].matches('\n').count() + 1;
+    files
+        .into_iter()
+        .zip(items)
+        .map(|(file, item)| {
+            build(file, |field| {
+                let at = yaml_key_offset(&contents, &item, field)
+                    .or_else(|| yaml_key_offset(&contents, &item, "loan"))
+                    .unwrap_or(item.start);
+                line_of(at)
+            })
+        })
+        .collect()
+}
+
+// The schema check for one scenario, `text`, a slice of the file `contents`
+// that is already known to be well-formed JSON: deserializes it, then builds
+// the loan, placing any error at a line of the whole file.
+fn validate(contents: &str, text: &str) -> Result<Scenario, ScenarioError> {
+    let offset = text.as_ptr() as usize - contents.as_ptr() as usize;
+    let line_of = |at: usize| contents[..offset + at].matches('\n').count() + 1;
+
+    let file: ScenarioFile = serde_json::from_str(text).map_err(|err| {
+        let at = byte_offset(text, err.line(), err.column());
+        // serde_json appends the position itself; it is reported separately.
+        let message = err.to_string();
+        let reason = message.rsplit_once(" at line ").map_or(message.as_str(), |(reason, _)| reason);
+        let field = quoted_name(reason)
+            .filter(|_| reason.starts_with("unknown field") || reason.starts_with("missing field"))
+            .or_else(|| key_before(&text[..at]));
+        ScenarioError::Schema(SchemaError {
+            line: line_of(at),
+            field,
+            reason: reason.to_string(),
+        })
+    })?;
+
+    build(file, |field| {
+        line_of(key_offset(text, field).or_else(|| key_offset(text, "loan")).unwrap_or(0))
+    })
+}
+
+// Builds the loan of a scenario that deserialized, placing any error on the
+// line `line_of` gives for the field at fault.
+fn build(file: ScenarioFile, line_of: impl Fn(&str) -> usize) -> Result<Scenario, ScenarioError> {
+    let loan = file.loan.build().map_err(|err| {
+        let (field, reason) = match err {
+            BuildError::Missing(field) => (field, "is required"),
+            BuildError::Invalid { field, reason } => (field, reason),
+        };
+        ScenarioError::Schema(SchemaError {
+            line: line_of(field),
+            field: Some(field.to_string()),
+            reason: reason.to_string(),
+        })
+    })?;
+
+    Ok(Scenario {
+        loan,
+        rate_changes: file.rate_changes,
+        early_payments: file.early_payments,
+    })
+}
+
+// A YAML deserialization error as a `Schema` error. serde_yaml reports the
+// position itself and prefixes the path to the value, e.g. `.[1].loan.principal: `.
+fn yaml_schema_error(err: serde_yaml::Error) -> ScenarioError {
+    let line = err.location().map_or(1, |location| location.line());
+    let message = err.to_string();
+    let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+    let (path, reason) = match message.split_once(": ") {
+        Some((path, reason)) if path.starts_with('.') => (Some(path), reason),
+        _ => (None, message),
+    };
+    let field = quoted_name(reason)
+        .filter(|_| reason.starts_with("unknown field") || reason.starts_with("missing field"))
+        .or_else(|| {
+            let last = path?.rsplit('.').next()?;
+            let name = last.split('[').next().filter(|name| !name.is_empty())?;
+            Some(name.to_string())
+        });
+    ScenarioError::Schema(SchemaError {
+        line,
+        field,
+        reason: reason.to_string(),
+    })
+}
+
+// Byte ranges of the scenarios in a YAML file: one per top-level `-` item of a
+// block-style list, or the whole file for a single scenario. A flow-style list
+// yields no items, and its errors are placed by key alone.
+fn yaml_items(contents: &str, is_list: bool) -> Vec<Range<usize>> {
+    if !is_list {
+        return std::iter::once(0..contents.len()).collect();
+    }
+
+    let mut starts = Vec::new();
+    let mut indent = None;
+    let mut at = 0;
+    for line in contents.split_inclusive('\n') {
+        let body = line.trim_start_matches(' ');
+        let is_item = body
+            .strip_prefix('-')
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        let skip = body.trim().is_empty() || body.starts_with('#') || body.starts_with("---");
+        if !skip {
+            let depth = line.len() - body.len();
+            match indent {
+                None if is_item => indent = Some(depth),
+                None => return Vec::new(),
+                Some(_) => {}
+            }
+            if is_item && indent == Some(depth) {
+                starts.push(at);
+            }
+        }
+        at += line.len();
+    }
+
+    let ends = starts.iter().skip(1).copied().chain([contents.len()]);
+    starts.iter().zip(ends).map(|(&start, end)| start..end).collect()
+}
+
+// Where the line holding `key:` starts within `item` of `contents`, allowing for
+// list dashes and quotes around the key.
+fn yaml_key_offset(contents: &str, item: &Range<usize>, key: &str) -> Option<usize> {
+    let mut at = item.start;
+    for line in contents[item.clone()].split_inclusive('\n') {
+        let body = line.trim_start_matches([' ', '-']);
+        let rest = [key.to_string(), format!("\"{}\"", key), format!("'{}'", key)]
+            .iter()
+            .find_map(|name| body.strip_prefix(name.as_str()));
+        if rest.is_some_and(|rest| rest.trim_start().starts_with(':')) {
+            return Some(at);
+        }
+        at += line.len();
+    }
+    None
+}
+
+// Byte offset of 1-based `line` and `column` in `text`, as serde_json reports them.
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let start: usize = text.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum();
+    (start + column.saturating_sub(1)).min(text.len())
+}
+
+// Where `"key":` first appears in `text`.
+fn key_offset(text: &str, key: &str) -> Option<usize> {
+    let quoted = format!("\"{}\"", key);
+    text.match_indices(&quoted)
+        .find(|(at, _)| text[at + quoted.len()..].trim_start().starts_with(':'))
+        .map(|(at, _)| at)
+}
+
+// The last `"key":` in `text`, i.e. the field whose value an error points into.
+fn key_before(text: &str) -> Option<String> {
+    let colon = text.rfind(':')?;
+    let key = text[..colon].trim_end().strip_suffix('"')?;
+    let start = key.rfind('"')?;
+    Some(key[start + 1..].to_string())
+}
+
+// The first `name` in backticks, as serde quotes field names.
+fn quoted_name(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once('`')?;
+    let (name, _) = rest.split_once('`')?;
+    Some(name.to_string())
+}
+
+impl Scenario {
+    /// Generates the schedule, then applies the rate changes in order followed
+    /// by the early payments in period order.
+    pub fn run(&self) -> Result<(Loan, Vec<PaymentSchedule>), ScenarioError> {
+        let mut loan = self.loan.clone();
+        let mut schedule = loan.generate_schedule();
+
+        for change in &self.rate_changes {
+            loan.adjust_rate(change.rate, change.from_period, &mut schedule)
+                .map_err(ScenarioError::RateChange)?;
+        }
+
+        let early_payments: Vec<_> = self
+            .early_payments
+            .iter()
+            .map(|p| (p.period, p.amount, p.strategy))
+            .collect();
+        loan.apply_early_payments(&early_payments, &mut schedule)
+            .map_err(ScenarioError::EarlyPayment)?;
+
+        Ok((loan, schedule))
+    }
+}
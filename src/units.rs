@@ -0,0 +1,115 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// An amount of money, so it cannot be passed where a period is expected.
+/// Public amount parameters take `impl Into<Money>`, so a plain `Decimal`
+/// still works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(pub Decimal);
+
+/// An absolute loan period: it counts `done_months`, like `PaymentSchedule::period`.
+/// Public period parameters take `impl Into<Period>`, so a plain `u32` still works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Period(pub u32);
+
+impl From<Decimal> for Money {
+    fn from(value: Decimal) -> Self {
+        Money(value)
+    }
+}
+
+impl From<Money> for Decimal {
+    fn from(value: Money) -> Self {
+        value.0
+    }
+}
+
+impl From<u32> for Period {
+    fn from(value: u32) -> Self {
+        Period(value)
+    }
+}
+
+impl From<Period> for u32 {
+    fn from(value: Period) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        Money(iter.map(|m| m.0).sum())
+    }
+}
+
+// Periods only move forward or back by a count; adding two periods is meaningless.
+impl Add<u32> for Period {
+    type Output = Period;
+
+    fn add(self, rhs: u32) -> Period {
+        Period(self.0.saturating_add(rhs))
+    }
+}
+
+impl Sub for Period {
+    type Output = u32;
+
+    /// Number of periods from `rhs` to `self`; zero if `rhs` is later.
+    fn sub(self, rhs: Period) -> u32 {
+        self.0.saturating_sub(rhs.0)
+    }
+}
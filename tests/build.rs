@@ -0,0 +1,86 @@
+//! Parameter checks in `LoanBuilder::build` and `Loan::new`.
+
+use chrono::NaiveDate;
+use prepayment::{BuildError, Loan, LoanBuilder, RepaymentMethod, ValidationError};
+use rust_decimal_macros::dec;
+
+fn builder() -> LoanBuilder {
+    LoanBuilder::new()
+        .principal(dec!(100000))
+        .annual_rate(dec!(6))
+        .total_months(360)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .method(RepaymentMethod::EqualInstallment)
+}
+
+#[test]
+fn rate_above_one_hundred_percent_is_rejected() {
+    let err = builder().annual_rate(dec!(500)).build().unwrap_err();
+
+    assert_eq!(err, BuildError::Invalid { field: "annual_rate", reason: "must be at most 100" });
+}
+
+#[test]
+fn extreme_stepped_rate_does_not_overflow_the_annuity() {
+    let loan = builder().annual_rate(dec!(100)).build().unwrap().with_rate_change(dec!(500), 2);
+    let schedule = loan.generate_schedule();
+
+    assert_eq!(schedule.len(), 360);
+    // (1 + r)^359 overflows, so the installment is its limit, the interest alone.
+    assert!(schedule[1].principal_payment >= dec!(0));
+    assert!(schedule[1].principal_payment < dec!(0.01));
+}
+
+#[test]
+fn fully_paid_term_is_a_descriptive_error() {
+    let start = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    let err = Loan::new(dec!(100000), dec!(6), 60, 60, start, RepaymentMethod::EqualPrincipal).unwrap_err();
+
+    assert_eq!(err, BuildError::Invalid { field: "done_months", reason: "must be less than total_months" });
+    assert_eq!(err.to_string(), "invalid loan field `done_months`: must be less than total_months");
+
+    let err = builder().done_months(360).build().unwrap_err();
+    assert_eq!(err, BuildError::Invalid { field: "done_months", reason: "must be less than total_months" });
+}
+
+#[test]
+fn negative_rate_is_rejected() {
+    let start = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    let err = Loan::new(dec!(100000), dec!(-0.5), 0, 360, start, RepaymentMethod::EqualPrincipal).unwrap_err();
+    assert_eq!(err, BuildError::Invalid { field: "annual_rate", reason: "must not be negative" });
+
+    let err = builder().annual_rate(dec!(-0.01)).build().unwrap_err();
+    assert_eq!(err, BuildError::Invalid { field: "annual_rate", reason: "must not be negative" });
+}
+
+#[test]
+fn zero_rate_schedule_is_interest_free() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let loan = builder().annual_rate(dec!(0)).total_months(7).method(method).build().unwrap();
+        let schedule = loan.generate_schedule();
+
+        assert_eq!(schedule.len(), 7);
+        for p in &schedule {
+            assert_eq!(p.interest, dec!(0), "{:?}", method);
+            assert_eq!(p.total_payment, p.principal_payment, "{:?}", method);
+        }
+        assert_eq!(loan.total_principal_paid(&schedule), dec!(100000), "{:?}", method);
+    }
+}
+
+#[test]
+fn validate_reports_rate_steps_outside_the_term() {
+    let loan = builder().done_months(12).build().unwrap();
+    assert!(loan.clone().with_rate_change(dec!(4), 13).with_rate_change(dec!(5), 360).validate().is_empty());
+
+    let errors = loan
+        .with_rate_change(dec!(4), 0)
+        .with_rate_change(dec!(5), 12)
+        .with_rate_change(dec!(3), 361)
+        .validate();
+    assert_eq!(
+        errors,
+        [0, 12, 361].map(|period| ValidationError::RateStepOutOfRange { period, first: 13, last: 360 })
+    );
+    assert_eq!(errors[0].to_string(), "rate schedule period 0 is outside 13..=360");
+}
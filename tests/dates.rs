@@ -0,0 +1,65 @@
+//! Month stepping with `add_months`/`next_month` and the payment dates they give.
+
+use chrono::{Datelike, NaiveDate};
+use prepayment::{add_months, next_month, LoanBuilder};
+use rust_decimal_macros::dec;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn month_end_clamps_to_leap_february() {
+    assert_eq!(next_month(date(2024, 1, 31)), date(2024, 2, 29));
+    assert_eq!(add_months(date(2024, 1, 31), 1), date(2024, 2, 29));
+    assert_eq!(add_months(date(2023, 12, 30), 2), date(2024, 2, 29));
+}
+
+#[test]
+fn month_end_clamps_to_common_february() {
+    assert_eq!(next_month(date(2023, 1, 31)), date(2023, 2, 28));
+    assert_eq!(add_months(date(2023, 1, 29), 1), date(2023, 2, 28));
+    assert_eq!(add_months(date(2022, 12, 31), 2), date(2023, 2, 28));
+}
+
+#[test]
+fn stepping_from_the_anchor_restores_the_day() {
+    let anchor = date(2023, 1, 31);
+    assert_eq!(add_months(anchor, 1), date(2023, 2, 28));
+    assert_eq!(add_months(anchor, 2), date(2023, 3, 31));
+    assert_eq!(add_months(anchor, 13), date(2024, 2, 29));
+}
+
+#[test]
+fn far_future_saturates() {
+    assert_eq!(add_months(NaiveDate::MAX, 1), NaiveDate::MAX);
+}
+
+#[test]
+fn leap_day_anchor_steps_across_years() {
+    let anchor = date(2024, 2, 29);
+    assert_eq!(add_months(anchor, 12), date(2025, 2, 28));
+    assert_eq!(add_months(anchor, 13), date(2025, 3, 29));
+    assert_eq!(add_months(anchor, 24), date(2026, 2, 28));
+    assert_eq!(add_months(anchor, 36), date(2027, 2, 28));
+    assert_eq!(add_months(anchor, 48), date(2028, 2, 29));
+}
+
+#[test]
+fn leap_day_start_schedule_restores_the_29th() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(60000))
+        .annual_rate(dec!(4.2))
+        .total_months(60)
+        .start_date(date(2024, 2, 29))
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    let februaries: Vec<NaiveDate> = schedule.iter().map(|p| p.payment_date).filter(|d| d.month() == 2).collect();
+    assert_eq!(
+        februaries,
+        [date(2024, 2, 29), date(2025, 2, 28), date(2026, 2, 28), date(2027, 2, 28), date(2028, 2, 29)]
+    );
+    assert!(schedule.iter().filter(|p| p.payment_date.month() != 2).all(|p| p.payment_date.day() == 29));
+}
@@ -0,0 +1,56 @@
+//! Interest accrual under each `DayCount` convention.
+
+use chrono::NaiveDate;
+use prepayment::{DayCount, LoanBuilder, PaymentSchedule};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+// A year of interest-only rows on 1,000,000 at the 4.35% benchmark rate, so
+// the balance never moves and only the day count varies.
+fn first_year(day_count: DayCount) -> Vec<PaymentSchedule> {
+    let loan = LoanBuilder::new()
+        .principal(dec!(1000000))
+        .annual_rate(dec!(4.35))
+        .total_months(24)
+        .grace_months(12)
+        .start_date(date(2024, 1, 20))
+        .day_count(day_count)
+        .build()
+        .unwrap();
+    let mut schedule = loan.generate_schedule();
+    schedule.truncate(12);
+    schedule
+}
+
+#[test]
+fn chinese_30_360_year_matches_the_bank_figure() {
+    let schedule = first_year(DayCount::Chinese30_360);
+
+    // 1,000,000 × 4.35% / 12 = 3,625.00 every month whatever its length, and
+    // 43,500.00 for the year.
+    assert!(schedule.iter().all(|p| p.interest == dec!(3625)));
+    assert_eq!(schedule.iter().map(|p| p.interest).sum::<Decimal>(), dec!(43500));
+}
+
+#[test]
+fn chinese_30_360_stub_accrues_per_actual_day_over_360() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(1000000))
+        .annual_rate(dec!(4.35))
+        .total_months(12)
+        .start_date(date(2024, 1, 10))
+        .first_payment_date(date(2024, 1, 20))
+        .day_count(DayCount::Chinese30_360)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    // 1,000,000 × 4.35% / 360 × 10 days = 1,208.33.
+    assert_eq!(schedule[0].interest, dec!(1208.33));
+    // Full months afterwards accrue a twelfth of the year on the balance.
+    assert_eq!(schedule[1].interest, (schedule[1].remaining_principal * dec!(0.0435) / dec!(12)).round_dp(2));
+}
@@ -0,0 +1,98 @@
+//! Payment holidays from `Loan::defer_payment` and the rewrites that follow.
+
+use chrono::NaiveDate;
+use prepayment::{
+    EarlyPaymentError, Loan, LoanBuilder, PaymentSchedule, PaymentStatus, PrepaymentStrategy, RepaymentMethod,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn deferred(method: RepaymentMethod) -> (Loan, Vec<PaymentSchedule>) {
+    let mut loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .method(method)
+        .build()
+        .unwrap();
+    let mut schedule = loan.generate_schedule();
+    loan.defer_payment(4, 2, &mut schedule).unwrap();
+    (loan, schedule)
+}
+
+// The balance closes to zero and each row's opening balance, before any
+// early payment, is the last one's closing balance plus whatever it capitalized.
+fn assert_ties_out(loan: &Loan, schedule: &[PaymentSchedule]) {
+    for pair in schedule.windows(2) {
+        let capitalized = match pair[0].status {
+            PaymentStatus::Deferred => pair[0].interest,
+            _ => Decimal::ZERO,
+        };
+        let closing = pair[0].remaining_principal - pair[0].principal_payment + capitalized;
+        let opening = pair[1].remaining_principal + pair[1].early_payment.unwrap_or_default();
+        assert_eq!(opening, closing, "period {}", pair[1].period);
+    }
+    assert_eq!(loan.rounding_residual(schedule), dec!(0));
+}
+
+#[test]
+fn fee_on_a_deferred_row_charges_only_the_fee() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let (mut loan, mut schedule) = deferred(method);
+        assert_eq!(schedule[3].status, PaymentStatus::Deferred);
+
+        loan.add_fee(4, dec!(25), &mut schedule).unwrap();
+
+        assert_eq!(schedule[3].total_payment, dec!(25));
+        assert_ties_out(&loan, &schedule);
+    }
+}
+
+#[test]
+fn rate_change_keeps_the_holiday() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let (mut loan, mut schedule) = deferred(method);
+
+        loan.adjust_rate(dec!(4.8), 3, &mut schedule).unwrap();
+
+        // The holiday keeps the rate its interest was capitalized at, and
+        // nothing but that interest is added to the balance.
+        for p in &schedule[3..5] {
+            assert_eq!(p.status, PaymentStatus::Deferred);
+            assert_eq!(p.interest_rate, dec!(6));
+            assert_eq!(p.total_payment, dec!(0));
+        }
+        assert_eq!(schedule[5].interest_rate, dec!(4.8));
+        assert_ties_out(&loan, &schedule);
+    }
+}
+
+#[test]
+fn prepayment_before_a_holiday_keeps_it() {
+    for strategy in [PrepaymentStrategy::ShortenTerm, PrepaymentStrategy::ReduceInstallment] {
+        let (mut loan, mut schedule) = deferred(RepaymentMethod::EqualInstallment);
+
+        loan.make_early_payment(dec!(1000), 2, strategy, &mut schedule).unwrap();
+
+        assert_eq!(schedule[3].status, PaymentStatus::Deferred);
+        assert_eq!(schedule[3].total_payment, dec!(0));
+        assert_ties_out(&loan, &schedule);
+    }
+}
+
+#[test]
+fn deferral_past_the_last_possible_period_is_an_error() {
+    let mut loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .build()
+        .unwrap();
+    let mut schedule = loan.generate_schedule();
+    let before = (loan.clone(), schedule.clone());
+
+    assert_eq!(loan.defer_payment(3, u32::MAX, &mut schedule), Err(EarlyPaymentError::TermOverflow));
+    assert_eq!((loan, schedule), before);
+}
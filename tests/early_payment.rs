@@ -0,0 +1,187 @@
+//! Prepayments applied with the `make_early_payment` family.
+
+use chrono::NaiveDate;
+use prepayment::{EarlyPaymentError, Loan, LoanBuilder, PaymentSchedule, PrepaymentStrategy, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+fn loan(method: RepaymentMethod) -> (Loan, Vec<PaymentSchedule>) {
+    let loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .method(method)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+    (loan, schedule)
+}
+
+#[test]
+fn prepayments_at_the_same_period_add_up() {
+    for strategy in [PrepaymentStrategy::ShortenTerm, PrepaymentStrategy::ReduceInstallment] {
+        let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+
+        loan.make_early_payment(dec!(900), 3, strategy, &mut schedule).unwrap();
+        loan.make_early_payment(dec!(900), 3, strategy, &mut schedule).unwrap();
+
+        assert_eq!(schedule[2].early_payment, Some(dec!(1800)));
+        assert_eq!(loan.total_principal_paid(&schedule), dec!(12000));
+        assert_eq!(loan.rounding_residual(&schedule), dec!(0));
+    }
+}
+
+#[test]
+fn payoff_keeps_an_earlier_prepayment_at_the_same_period() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+
+    loan.make_early_payment(dec!(900), 3, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+    let settlement = loan.payoff(3, &mut schedule).unwrap();
+
+    assert_eq!(schedule.len(), 3);
+    assert_eq!(loan.total_principal_paid(&schedule), dec!(12000));
+    // The 10000 balance before period 3, plus interest on the 9100 left after
+    // the first prepayment.
+    assert_eq!(settlement, dec!(10045.50));
+}
+
+#[test]
+fn annual_limit_counts_earlier_prepayments_at_the_same_period() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+    loan.annual_prepayment_limit = Some(dec!(1000));
+
+    loan.make_early_payment(dec!(900), 3, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+    let err = loan
+        .make_early_payment(dec!(900), 3, PrepaymentStrategy::ShortenTerm, &mut schedule)
+        .unwrap_err();
+
+    assert_eq!(err, EarlyPaymentError::AnnualLimitExceeded { year: 2024, remaining: dec!(100) });
+    assert_eq!(schedule[2].early_payment, Some(dec!(900)));
+}
+
+#[test]
+fn payoff_respects_the_annual_limit() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+    loan.annual_prepayment_limit = Some(dec!(1000));
+    let before = schedule.clone();
+
+    let err = loan.payoff(3, &mut schedule).unwrap_err();
+
+    assert_eq!(err, EarlyPaymentError::AnnualLimitExceeded { year: 2024, remaining: dec!(1000) });
+    assert_eq!(schedule, before);
+}
+
+#[test]
+fn recurring_prepayment_every_zero_months_is_an_error() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+
+    let err = loan
+        .make_recurring_early_payment(dec!(100), 0, 3, PrepaymentStrategy::ShortenTerm, &mut schedule)
+        .unwrap_err();
+
+    assert_eq!(err, EarlyPaymentError::ZeroInterval);
+}
+
+#[test]
+fn shortened_schedule_ends_on_the_row_that_closes_the_balance() {
+    let cases = [
+        (dec!(10000), dec!(3.3), 7, dec!(1234.56)),
+        (dec!(99999.99), dec!(4.9), 37, dec!(33333.33)),
+        (dec!(536714.20), dec!(4.2), 231, dec!(99839.39)),
+    ];
+    for (principal, rate, months, extra) in cases {
+        for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+            let mut loan = LoanBuilder::new()
+                .principal(principal)
+                .annual_rate(rate)
+                .total_months(months)
+                .start_date(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap())
+                .method(method)
+                .build()
+                .unwrap();
+            let mut schedule = loan.generate_schedule();
+
+            loan.make_early_payment(extra, 2, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+
+            let last = schedule.last().unwrap();
+            assert!(schedule.iter().all(|p| p.principal_payment > dec!(0)));
+            assert_eq!(last.remaining_principal, last.principal_payment);
+            assert_eq!(loan.total_principal_paid(&schedule), principal);
+        }
+    }
+}
+
+#[test]
+fn periods_up_to_done_months_are_out_of_range() {
+    let mut loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .done_months(4)
+        .total_months(16)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .build()
+        .unwrap();
+    let mut schedule = loan.generate_schedule();
+    let before = schedule.clone();
+
+    for period in [4, 3] {
+        let err = loan
+            .make_early_payment(dec!(100), period, PrepaymentStrategy::ShortenTerm, &mut schedule)
+            .unwrap_err();
+        assert_eq!(err, EarlyPaymentError::PeriodOutOfRange, "period {}", period);
+    }
+    assert_eq!(schedule, before);
+
+    loan.make_early_payment(dec!(100), 5, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+    assert_eq!(schedule[0].early_payment, Some(dec!(100)));
+}
+
+#[test]
+fn sequential_reduce_installments_each_start_from_the_current_balance() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+
+    // 10000 owed at period 3, less 1200, over 10 periods.
+    assert_eq!(loan.make_early_payment_reduce(dec!(1200), 3, &mut schedule).unwrap(), dec!(880));
+    // 8800 - 2 * 880 owed at period 5, less 880, over 8 periods.
+    assert_eq!(loan.make_early_payment_reduce(dec!(880), 5, &mut schedule).unwrap(), dec!(770));
+
+    assert_eq!(loan.monthly_principal_payment, dec!(1000));
+    assert!(schedule[2..4].iter().all(|p| p.principal_payment == dec!(880)));
+    assert!(schedule[4..].iter().all(|p| p.principal_payment == dec!(770)));
+    assert_eq!(schedule.len(), 12);
+    assert_eq!(loan.total_principal_paid(&schedule), dec!(12000));
+}
+
+#[test]
+fn prepaying_max_allowed_ends_the_schedule_whatever_the_strategy() {
+    for strategy in [PrepaymentStrategy::ShortenTerm, PrepaymentStrategy::ReduceInstallment] {
+        let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+        let max_allowed = match loan.make_early_payment(dec!(20000), 3, strategy, &mut schedule) {
+            Err(EarlyPaymentError::Overpayment { max_allowed }) => max_allowed,
+            other => panic!("expected an overpayment, got {:?}", other),
+        };
+        assert_eq!(max_allowed, dec!(10000));
+
+        loan.make_early_payment(max_allowed, 3, strategy, &mut schedule).unwrap();
+
+        assert_eq!(schedule.len(), 3, "{:?}", strategy);
+        assert_eq!(schedule[2].early_payment, Some(dec!(10000)));
+        assert_eq!(schedule[2].total_payment, dec!(0));
+        let summary = loan.summary(&schedule);
+        assert_eq!(summary.number_of_payments, 3);
+        assert_eq!(summary.months_saved, 9);
+        assert_eq!(summary.payoff_date, Some(NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()));
+        assert_eq!(loan.total_principal_paid(&schedule), dec!(12000));
+    }
+}
+
+#[test]
+fn recurring_prepayment_stops_at_the_last_possible_period() {
+    let (mut loan, mut schedule) = loan(RepaymentMethod::EqualPrincipal);
+
+    loan.make_recurring_early_payment(dec!(100), u32::MAX, 3, PrepaymentStrategy::ReduceInstallment, &mut schedule)
+        .unwrap();
+
+    assert_eq!(loan.early_payment_count(&schedule), 1);
+    assert_eq!(schedule[2].early_payment, Some(dec!(100)));
+}
@@ -0,0 +1,92 @@
+//! 等额本息 loans keep a level payment through every schedule rewrite.
+
+use chrono::NaiveDate;
+use prepayment::{Loan, LoanBuilder, PaymentSchedule, PrepaymentStrategy, RepaymentMethod};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn loan() -> (Loan, Vec<PaymentSchedule>) {
+    let loan = LoanBuilder::new()
+        .principal(dec!(100000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .method(RepaymentMethod::EqualInstallment)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+    (loan, schedule)
+}
+
+// Every row of `rows` pays `installment`, except that the last one may be a
+// few cents off as it closes the balance.
+fn assert_level(rows: &[PaymentSchedule], installment: Decimal) {
+    let (last, level) = rows.split_last().unwrap();
+    for p in level {
+        assert_eq!(p.total_payment, installment, "period {}", p.period);
+    }
+    assert!((last.total_payment - installment).abs() < dec!(0.05), "period {}", last.period);
+    assert_eq!(last.remaining_principal, last.principal_payment);
+}
+
+#[test]
+fn reduce_installment_prepayment_keeps_the_payment_level() {
+    let (mut loan, mut schedule) = loan();
+
+    let installment = loan.make_early_payment_reduce(dec!(20000), 4, &mut schedule).unwrap();
+
+    assert_eq!(schedule.len(), 12);
+    assert_eq!(installment, schedule[3].total_payment);
+    assert!(installment < dec!(8606.64));
+    assert_level(&schedule[3..], installment);
+}
+
+#[test]
+fn shorten_term_prepayment_keeps_the_original_payment() {
+    let (mut loan, mut schedule) = loan();
+    let installment = schedule[0].total_payment;
+
+    loan.make_early_payment(dec!(20000), 4, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+
+    // The last row pays off what is left, less than a full installment.
+    let (last, level) = schedule.split_last().unwrap();
+    assert!(schedule.len() < 12);
+    for p in level {
+        assert_eq!(p.total_payment, installment, "period {}", p.period);
+    }
+    assert!(last.total_payment <= installment);
+    assert_eq!(loan.total_principal_paid(&schedule), dec!(100000));
+}
+
+#[test]
+fn rate_change_reamortizes_like_a_rate_step() {
+    let (mut loan, mut schedule) = loan();
+    let stepped = loan.clone().with_rate_change(dec!(3), 4).generate_schedule();
+
+    loan.adjust_rate(dec!(3), 4, &mut schedule).unwrap();
+
+    assert_level(&schedule[3..], schedule[3].total_payment);
+    assert_eq!(schedule, stepped);
+}
+
+#[test]
+fn drawdown_reamortizes_to_a_level_payment() {
+    let (mut loan, mut schedule) = loan();
+
+    loan.add_drawdown(dec!(10000), 4, &mut schedule).unwrap();
+
+    assert!(schedule[3].total_payment > schedule[0].total_payment);
+    assert_level(&schedule[3..], schedule[3].total_payment);
+}
+
+#[test]
+fn deferral_reamortizes_to_a_level_payment() {
+    let (mut loan, mut schedule) = loan();
+
+    loan.defer_payment(4, 2, &mut schedule).unwrap();
+
+    assert_eq!(schedule.len(), 14);
+    assert_eq!(schedule[3].total_payment, dec!(0));
+    assert_eq!(schedule[4].total_payment, dec!(0));
+    assert_level(&schedule[5..], schedule[5].total_payment);
+}
@@ -0,0 +1,44 @@
+//! Renderers write amounts at the loan's currency scale.
+
+use chrono::NaiveDate;
+use prepayment::{schedule_to_html, schedule_to_ics, schedule_to_markdown, LoanBuilder, Locale, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+#[test]
+fn whole_currency_units_render_without_decimals() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(3000000))
+        .annual_rate(dec!(1.5))
+        .total_months(24)
+        .start_date(NaiveDate::from_ymd_opt(2024, 4, 25).unwrap())
+        .method(RepaymentMethod::EqualInstallment)
+        .currency_scale(0)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    let markdown = schedule_to_markdown(&schedule, Locale::En, loan.currency_scale);
+    assert!(markdown.contains("| 3750 | 123212 | 3000000 | 126962 |"), "{}", markdown);
+
+    let html = schedule_to_html(&loan, &schedule, &loan.summary(&schedule), Locale::En);
+    assert!(html.contains("<td>3000000</td>"), "{}", html);
+    assert!(html.contains("<td class=\"num\">3750</td>"), "{}", html);
+
+    let ics = schedule_to_ics(&schedule, "Loan", loan.currency_scale);
+    assert!(ics.contains("SUMMARY:Loan #1: 126962\r\n"), "{}", ics);
+}
+
+#[test]
+fn cents_keep_two_places() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    let markdown = schedule_to_markdown(&schedule, Locale::En, loan.currency_scale);
+    assert!(markdown.contains("| 60.00 | 1000.00 | 12000.00 | 1060.00 |"), "{}", markdown);
+}
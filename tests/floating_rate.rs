@@ -0,0 +1,54 @@
+//! LPR-based floating rates and their contractual floor and cap.
+
+use chrono::NaiveDate;
+use prepayment::{Loan, LoanBuilder, RateMode};
+use rust_decimal_macros::dec;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn floating_loan() -> Loan {
+    LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(4))
+        .total_months(12)
+        .start_date(date(2024, 1, 15))
+        .rate_mode(RateMode::Floating {
+            spread: dec!(0.5),
+            resets: vec![
+                (date(2024, 1, 1), dec!(2)),
+                (date(2024, 5, 1), dec!(4)),
+                (date(2024, 9, 1), dec!(6)),
+            ],
+        })
+        .rate_floor(dec!(3))
+        .rate_cap(dec!(5))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn lpr_plus_spread_is_clamped_between_floor_and_cap() {
+    let schedule = floating_loan().generate_schedule();
+
+    // 2 + 0.5 is below the floor, 4 + 0.5 is inside, 6 + 0.5 is above the cap.
+    let rates: Vec<_> = schedule.iter().map(|p| p.interest_rate).collect();
+    assert_eq!(rates[..4], [dec!(3); 4]);
+    assert_eq!(rates[4..8], [dec!(4.5); 4]);
+    assert_eq!(rates[8..], [dec!(5); 4]);
+    // 12000 at 3% for a month.
+    assert_eq!(schedule[0].interest, dec!(30));
+}
+
+#[test]
+fn adjust_rate_is_clamped_too() {
+    let mut loan = floating_loan();
+    let mut schedule = loan.generate_schedule();
+
+    loan.adjust_rate(dec!(1.2), 2, &mut schedule).unwrap();
+    assert!(schedule[1..].iter().all(|p| p.interest_rate == dec!(3)));
+
+    loan.adjust_rate(dec!(9), 6, &mut schedule).unwrap();
+    assert!(schedule[5..].iter().all(|p| p.interest_rate == dec!(5)));
+}
@@ -0,0 +1,49 @@
+//! Comparing payment frequencies with `Loan::compare_frequency`.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, PaymentFrequency, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+#[test]
+fn same_frequency_with_rate_steps_compares_equal() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let loan = LoanBuilder::new()
+            .principal(dec!(200000))
+            .annual_rate(dec!(4.2))
+            .done_months(12)
+            .total_months(120)
+            .start_date(NaiveDate::from_ymd_opt(2024, 3, 10).unwrap())
+            .method(method)
+            .rate_schedule(vec![(25, dec!(5.1)), (61, dec!(3.9))])
+            .build()
+            .unwrap();
+
+        let comparison = loan.compare_frequency(PaymentFrequency::Monthly);
+
+        assert_eq!(comparison.interest_saved, dec!(0));
+        assert_eq!(comparison.payoff_date_difference, 0);
+        assert_eq!(comparison.periods, 108);
+    }
+}
+
+#[test]
+fn rate_step_at_the_first_remaining_payment_applies_throughout() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(200000))
+        .annual_rate(dec!(4.2))
+        .done_months(12)
+        .total_months(120)
+        .start_date(NaiveDate::from_ymd_opt(2024, 3, 10).unwrap())
+        .method(RepaymentMethod::EqualPrincipal)
+        .rate_schedule(vec![(13, dec!(9))])
+        .build()
+        .unwrap();
+
+    // The step is in force from the first remaining payment, so biweekly pays
+    // 9% throughout too and saves on the faster paydown alone.
+    let stepped = loan.compare_frequency(PaymentFrequency::BiWeekly);
+    let mut flat = loan.clone();
+    flat.rate_schedule.clear();
+    flat.annual_rate = dec!(9);
+    assert_eq!(stepped, flat.compare_frequency(PaymentFrequency::BiWeekly));
+}
@@ -0,0 +1,172 @@
+//! Golden-file checks: each loan's schedule is rendered with `schedule_to_csv`
+//! and compared byte for byte against `tests/fixtures/<name>.csv`. Run with
+//! `UPDATE_GOLDEN=1 cargo test` to regenerate the fixtures after an intended
+//! change, then review the diff.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use prepayment::{
+    schedule_to_csv, DayCount, Loan, LoanBuilder, Locale, PaymentFrequency, PaymentSchedule, PrepaymentStrategy, RateMode, RepaymentMethod,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn assert_golden(name: &str, loan: &Loan, schedule: &[PaymentSchedule]) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(format!("{}.csv", name));
+    let actual = schedule_to_csv(schedule, Locale::En, loan.currency_scale);
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &actual).expect("write golden file");
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("missing {} ({}); run with UPDATE_GOLDEN=1", path.display(), err));
+    assert!(actual == expected, "{} no longer matches {}", name, path.display());
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+// The loan `main` runs with no arguments, after its two rate changes and the
+// initial 43-installment prepayment.
+fn example_loan() -> (Loan, Vec<PaymentSchedule>) {
+    let mut loan = LoanBuilder::new()
+        .principal(dec!(536714.20))
+        .annual_rate(dec!(4.2))
+        .done_months(57)
+        .total_months(288)
+        .start_date(date(2024, 10, 19))
+        .method(RepaymentMethod::EqualPrincipal)
+        .build()
+        .unwrap();
+
+    let mut schedule = loan.generate_schedule();
+    loan.adjust_rate(dec!(3.9), 59, &mut schedule).unwrap();
+    loan.adjust_rate(dec!(3.55), 60, &mut schedule).unwrap();
+
+    let prepayment = (loan.monthly_principal_payment * Decimal::from(43)).round_dp(2);
+    loan.make_early_payment(prepayment, 58, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+
+    (loan, schedule)
+}
+
+#[test]
+fn example_shorten_term() {
+    let (mut loan, mut schedule) = example_loan();
+
+    let payment = (Decimal::from(10000) / loan.monthly_principal_payment).trunc() * loan.monthly_principal_payment;
+    loan.make_recurring_early_payment(payment, 3, 60, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+
+    assert_golden("example_shorten_term", &loan, &schedule);
+}
+
+#[test]
+fn example_reduce_installment() {
+    let (mut loan, mut schedule) = example_loan();
+
+    for period in 0..=schedule.len() as u32 {
+        let prepay = (59 + period) % 3 == 0;
+        let yearly = period > 12 && period % 12 == 0;
+        for _ in 0..(prepay as u32 + yearly as u32) {
+            let installment = loan
+                .row_for_period(&schedule, 59 + period)
+                .map_or(loan.monthly_principal_payment, |p| p.principal_payment);
+            let payment = (Decimal::from(10000) / installment).trunc() * installment;
+            let _ = loan.make_early_payment(payment, 59 + period, PrepaymentStrategy::ReduceInstallment, &mut schedule);
+        }
+    }
+
+    assert_golden("example_reduce_installment", &loan, &schedule);
+}
+
+#[test]
+fn csv_early_payment_is_blank_or_numeric() {
+    let (loan, schedule) = example_loan();
+    let csv = schedule_to_csv(&schedule, Locale::En, loan.currency_scale);
+    let column = csv.lines().next().unwrap().split(',').position(|c| c == "early_payment").unwrap();
+
+    let fields: Vec<&str> = csv.lines().skip(1).map(|line| line.split(',').nth(column).unwrap()).collect();
+    assert!(fields.iter().any(|f| f.is_empty()));
+    for field in fields.iter().filter(|f| !f.is_empty()) {
+        assert!(field.parse::<Decimal>().is_ok(), "`{}` is not a number", field);
+    }
+}
+
+#[test]
+fn equal_installment() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(536714.20))
+        .annual_rate(dec!(3.55))
+        .done_months(57)
+        .total_months(288)
+        .start_date(date(2024, 10, 19))
+        .method(RepaymentMethod::EqualInstallment)
+        .build()
+        .unwrap();
+
+    assert_golden("equal_installment", &loan, &loan.generate_schedule());
+}
+
+#[test]
+fn floating_rate_with_grace() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(300000))
+        .annual_rate(dec!(4.1))
+        .total_months(36)
+        .start_date(date(2024, 1, 31))
+        .method(RepaymentMethod::EqualInstallment)
+        .grace_months(6)
+        .day_count(DayCount::Actual365)
+        .rate_mode(RateMode::Floating {
+            spread: dec!(-0.3),
+            resets: vec![(date(2024, 1, 1), dec!(4.2)), (date(2025, 1, 1), dec!(3.6))],
+        })
+        .build()
+        .unwrap();
+
+    assert_golden("floating_rate_with_grace", &loan, &loan.generate_schedule());
+}
+
+#[test]
+fn biweekly_with_stub() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(50000))
+        .annual_rate(dec!(6))
+        .total_months(52)
+        .start_date(date(2024, 2, 19))
+        .first_payment_date(date(2024, 3, 1))
+        .frequency(PaymentFrequency::BiWeekly)
+        .day_count(DayCount::Chinese30_360)
+        .build()
+        .unwrap();
+
+    assert_golden("biweekly_with_stub", &loan, &loan.generate_schedule());
+}
+
+#[test]
+fn whole_currency_units() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(3000000))
+        .annual_rate(dec!(1.5))
+        .total_months(24)
+        .start_date(date(2024, 4, 25))
+        .method(RepaymentMethod::EqualInstallment)
+        .currency_scale(0)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    for p in &schedule {
+        assert_eq!(p.interest.fract(), Decimal::ZERO);
+        assert_eq!(p.principal_payment.fract(), Decimal::ZERO);
+        assert_eq!(p.total_payment.fract(), Decimal::ZERO);
+    }
+    assert_golden("whole_currency_units", &loan, &schedule);
+}
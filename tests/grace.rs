@@ -0,0 +1,68 @@
+//! Interest-only (宽限期) periods at the start of a loan.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+#[test]
+fn principal_is_untouched_in_grace_and_amortizes_after() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let loan = LoanBuilder::new()
+            .principal(dec!(12000))
+            .annual_rate(dec!(6))
+            .total_months(12)
+            .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+            .method(method)
+            .grace_months(3)
+            .build()
+            .unwrap();
+        let schedule = loan.generate_schedule();
+
+        assert_eq!(schedule.len(), 12);
+        for p in &schedule[..3] {
+            assert_eq!(p.principal_payment, dec!(0), "{:?}", method);
+            assert_eq!(p.remaining_principal, dec!(12000), "{:?}", method);
+            assert_eq!(p.interest, dec!(60), "{:?}", method);
+            assert_eq!(p.total_payment, p.interest, "{:?}", method);
+        }
+        assert!(schedule[3..].iter().all(|p| p.principal_payment > dec!(0)), "{:?}", method);
+        assert_eq!(loan.total_principal_paid(&schedule), dec!(12000), "{:?}", method);
+
+        let last = schedule.last().unwrap();
+        assert_eq!(last.remaining_principal, last.principal_payment, "{:?}", method);
+    }
+}
+
+#[test]
+fn equal_principal_installment_spreads_over_the_months_after_grace() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(9000))
+        .annual_rate(dec!(4.8))
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .grace_months(3)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    assert!(schedule[3..].iter().all(|p| p.principal_payment == dec!(1000)));
+}
+
+#[test]
+fn monthly_principal_payment_matches_the_rows_after_grace() {
+    for done_months in [0, 2, 6] {
+        let loan = LoanBuilder::new()
+            .principal(dec!(12000))
+            .annual_rate(dec!(6))
+            .done_months(done_months)
+            .total_months(12)
+            .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+            .grace_months(4)
+            .build()
+            .unwrap();
+        let schedule = loan.generate_schedule();
+
+        let first_amortizing = schedule.iter().find(|p| p.period > 4).unwrap();
+        assert_eq!(loan.monthly_principal_payment, first_amortizing.principal_payment, "done {}", done_months);
+    }
+}
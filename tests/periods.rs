@@ -0,0 +1,48 @@
+//! `PaymentSchedule::period` stays absolute and in range through every operation.
+
+use chrono::NaiveDate;
+use prepayment::{Loan, LoanBuilder, PaymentSchedule, PrepaymentStrategy, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+fn assert_periods(loan: &Loan, schedule: &[PaymentSchedule], step: &str) {
+    assert_eq!(schedule[0].period, loan.done_months + 1, "after {}", step);
+    for pair in schedule.windows(2) {
+        assert_eq!(pair[1].period, pair[0].period + 1, "after {}", step);
+    }
+    assert!(schedule.last().unwrap().period <= loan.months, "after {}", step);
+}
+
+#[test]
+fn periods_increase_and_stay_within_the_term() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let mut loan = LoanBuilder::new()
+            .principal(dec!(120000))
+            .annual_rate(dec!(4.9))
+            .done_months(6)
+            .total_months(60)
+            .start_date(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap())
+            .method(method)
+            .build()
+            .unwrap();
+        let mut schedule = loan.generate_schedule();
+        assert_periods(&loan, &schedule, "generate_schedule");
+
+        loan.make_early_payment(dec!(10000), 8, PrepaymentStrategy::ReduceInstallment, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "reduce installment");
+        loan.make_early_payment(dec!(5000), 8, PrepaymentStrategy::ReduceInstallment, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "second reduce installment");
+        loan.adjust_rate(dec!(3.8), 12, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "adjust_rate");
+        loan.defer_payment(14, 2, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "defer_payment");
+        loan.add_drawdown(dec!(3000), 20, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "add_drawdown");
+        loan.make_early_payment(dec!(20000), 24, PrepaymentStrategy::ShortenTerm, &mut schedule).unwrap();
+        assert_periods(&loan, &schedule, "shorten term");
+        loan.make_recurring_early_payment(dec!(2000), 3, 30, PrepaymentStrategy::ReduceInstallment, &mut schedule)
+            .unwrap();
+        assert_periods(&loan, &schedule, "recurring prepayments");
+
+        assert!(schedule.iter().all(|p| p.principal_payment >= dec!(0)), "{:?}", method);
+    }
+}
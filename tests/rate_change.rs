@@ -0,0 +1,51 @@
+//! Repricing a schedule with `Loan::adjust_rate`.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+#[test]
+fn adjust_rate_accepts_a_slice_that_starts_after_the_first_row() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .done_months(2)
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .method(RepaymentMethod::EqualPrincipal)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    let mut whole = schedule.clone();
+    loan.clone().adjust_rate(dec!(4.8), 6, &mut whole).unwrap();
+    let mut tail = schedule.clone();
+    loan.clone().adjust_rate(dec!(4.8), 6, &mut tail[3..]).unwrap();
+
+    for (a, b) in whole.iter().zip(&tail) {
+        assert_eq!((a.period, a.interest, a.total_payment), (b.period, b.interest, b.total_payment));
+    }
+}
+
+#[test]
+fn repriced_rows_keep_at_most_two_decimals() {
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        let mut loan = LoanBuilder::new()
+            .principal(dec!(98765.43))
+            .annual_rate(dec!(4.9))
+            .total_months(37)
+            .start_date(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap())
+            .method(method)
+            .build()
+            .unwrap();
+        let mut schedule = loan.generate_schedule();
+
+        loan.adjust_rate(dec!(3.333), 5, &mut schedule).unwrap();
+        loan.adjust_rate(dec!(4.0177), 20, &mut schedule).unwrap();
+
+        for p in &schedule {
+            assert_eq!(p.total_payment, p.total_payment.round_dp(2), "{:?} period {}", method, p.period);
+            assert_eq!(p.interest, p.interest.round_dp(2), "{:?} period {}", method, p.period);
+        }
+    }
+}
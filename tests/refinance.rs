@@ -0,0 +1,29 @@
+//! Moving a balance to new terms with `Loan::refinance`.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, RepaymentMethod};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+#[test]
+fn refinanced_loan_keeps_currency_scale() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(3000000))
+        .annual_rate(dec!(1.5))
+        .total_months(24)
+        .start_date(NaiveDate::from_ymd_opt(2024, 4, 25).unwrap())
+        .method(RepaymentMethod::EqualInstallment)
+        .currency_scale(0)
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    let (new_schedule, summary) = loan.refinance(6, dec!(0.9), 36, Decimal::ZERO, &schedule).unwrap();
+
+    assert_eq!(summary.balance_transferred.fract(), Decimal::ZERO);
+    for p in &new_schedule {
+        assert_eq!(p.interest.fract(), Decimal::ZERO);
+        assert_eq!(p.principal_payment.fract(), Decimal::ZERO);
+        assert_eq!(p.total_payment.fract(), Decimal::ZERO);
+    }
+}
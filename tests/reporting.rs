@@ -0,0 +1,68 @@
+//! Read-only views over a schedule: shares, progress and balances.
+
+use chrono::NaiveDate;
+use prepayment::{Loan, LoanBuilder, RepaymentMethod};
+use rust_decimal_macros::dec;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn whole_currency_loan() -> Loan {
+    LoanBuilder::new()
+        .principal(dec!(3000000))
+        .annual_rate(dec!(1.5))
+        .total_months(24)
+        .start_date(date(2024, 4, 25))
+        .method(RepaymentMethod::EqualInstallment)
+        .currency_scale(0)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn interest_share_keeps_two_places_for_whole_currency_units() {
+    let loan = whole_currency_loan();
+    let schedule = loan.generate_schedule();
+
+    // 3750 / 126962 of the first payment, not rounded to a whole percent.
+    assert_eq!(loan.interest_share(1, &schedule), dec!(2.95));
+    for (_, share) in loan.interest_shares(&schedule) {
+        assert!(share.scale() <= 2);
+    }
+}
+
+#[test]
+fn payoff_progress_keeps_two_places_for_whole_currency_units() {
+    let loan = whole_currency_loan();
+    let schedule = loan.generate_schedule();
+
+    assert_eq!(loan.payoff_progress(&schedule, date(2024, 4, 24)), dec!(0));
+    // 123212 of 3000000 repaid after the first payment.
+    assert_eq!(loan.payoff_progress(&schedule, date(2024, 4, 25)), dec!(4.11));
+    assert_eq!(loan.payoff_progress(&schedule, date(2026, 4, 25)), dec!(100));
+}
+
+#[test]
+fn balance_on_exact_payment_dates_and_mid_month() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(date(2024, 1, 15))
+        .build()
+        .unwrap();
+    let schedule = loan.generate_schedule();
+
+    // Before the first payment nothing has been repaid.
+    assert_eq!(loan.balance_on(&schedule, date(2024, 1, 14)), dec!(12000));
+    // A payment due on the date counts as made.
+    assert_eq!(loan.balance_on(&schedule, date(2024, 1, 15)), dec!(11000));
+    assert_eq!(loan.balance_on(&schedule, date(2024, 6, 15)), dec!(6000));
+    // Mid-month is the balance after the latest payment.
+    assert_eq!(loan.balance_on(&schedule, date(2024, 6, 30)), dec!(6000));
+    assert_eq!(loan.balance_on(&schedule, date(2024, 7, 14)), dec!(6000));
+    // On and after payoff.
+    assert_eq!(loan.balance_on(&schedule, date(2024, 12, 15)), dec!(0));
+    assert_eq!(loan.balance_on(&schedule, date(2027, 6, 1)), dec!(0));
+}
@@ -0,0 +1,42 @@
+//! The final row absorbs the rounding residual so the balance closes at zero.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, RepaymentMethod};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+#[test]
+fn principal_payments_sum_to_the_loan_for_awkward_terms() {
+    let cases = [
+        (dec!(100000), 0, 7),
+        (dec!(12345.67), 0, 13),
+        (dec!(1), 0, 3),
+        (dec!(0.05), 0, 12),
+        (dec!(999999.99), 0, 360),
+        (dec!(536714.20), 57, 288),
+        (dec!(20000.01), 5, 11),
+    ];
+
+    for method in [RepaymentMethod::EqualPrincipal, RepaymentMethod::EqualInstallment] {
+        for (principal, done_months, months) in cases {
+            let loan = LoanBuilder::new()
+                .principal(principal)
+                .annual_rate(dec!(4.35))
+                .done_months(done_months)
+                .total_months(months)
+                .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+                .method(method)
+                .build()
+                .unwrap();
+            let schedule = loan.generate_schedule();
+            let case = format!("{:?} {} over {} of {}", method, principal, months - done_months, months);
+
+            let paid: Decimal = schedule.iter().map(|p| p.principal_payment).sum();
+            assert_eq!(paid, principal, "{}", case);
+
+            let last = schedule.last().unwrap();
+            assert_eq!(last.remaining_principal - last.principal_payment, dec!(0), "{}", case);
+            assert!(schedule.iter().all(|p| p.principal_payment >= dec!(0)), "{}", case);
+        }
+    }
+}
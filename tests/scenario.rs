@@ -0,0 +1,244 @@
+//! Loading scenario files and the schema errors they report.
+
+use std::fs;
+use std::path::PathBuf;
+
+use prepayment::{load_scenario, load_scenario_yaml, load_scenarios, Scenario, ScenarioError, SchemaError};
+use rust_decimal_macros::dec;
+
+// Writes `contents` to a file of its own under the system temp directory.
+fn write(name: &str, contents: &str) -> PathBuf {
+    let extension = if contents.trim_start().starts_with(['{', '[']) { "json" } else { "yaml" };
+    let path = std::env::temp_dir().join(format!("prepayment-{}-{}.{}", std::process::id(), name, extension));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn scenarios(name: &str, contents: &str) -> Result<Vec<Scenario>, ScenarioError> {
+    let path = write(name, contents);
+    let result = load_scenarios(&path);
+    fs::remove_file(path).unwrap();
+    result
+}
+
+fn schema_error(result: Result<Vec<Scenario>, ScenarioError>) -> SchemaError {
+    match result {
+        Err(ScenarioError::Schema(err)) => err,
+        other => panic!("expected a schema error, got {:?}", other.map(|s| s.len())),
+    }
+}
+
+const VALID: &str = r#"{
+    "loan": {
+        "principal": "120000",
+        "annual_rate": "4.2",
+        "total_months": 120,
+        "start_date": "2024-01-15"
+    },
+    "early_payments": [{ "period": 12, "amount": "10000", "strategy": "ShortenTerm" }]
+}"#;
+
+// `VALID` followed by a second scenario whose loan table is `loan`.
+fn with_second(loan: &str) -> String {
+    format!("[\n{},\n{{\n    \"loan\": {{\n{}\n    }}\n}}\n]\n", VALID, loan)
+}
+
+#[test]
+fn loads_a_list_of_scenarios() {
+    let loaded = scenarios("valid", &format!("[{}, {}]", VALID, VALID)).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[1].early_payments.len(), 1);
+}
+
+#[test]
+fn single_scenario_uses_the_same_checks() {
+    let path = write("single", VALID);
+    assert!(load_scenario(&path).is_ok());
+    fs::remove_file(path).unwrap();
+
+    let path = write("single-bad", &VALID.replace("\"total_months\": 120", "\"total_months\": \"ten\""));
+    let err = load_scenario(&path).unwrap_err();
+    fs::remove_file(path).unwrap();
+
+    let ScenarioError::Schema(err) = err else {
+        panic!("expected a schema error, got {}", err);
+    };
+    assert_eq!((err.line, err.field.as_deref()), (5, Some("total_months")));
+}
+
+#[test]
+fn unknown_field_names_its_line_in_the_whole_file() {
+    let contents = with_second(
+        r#"        "principal": "1000",
+        "anual_rate": "4.2",
+        "total_months": 12,
+        "start_date": "2024-01-15""#,
+    );
+
+    let err = schema_error(scenarios("unknown", &contents));
+
+    assert_eq!(err.line, 14);
+    assert_eq!(err.field.as_deref(), Some("anual_rate"));
+    assert!(err.reason.starts_with("unknown field `anual_rate`"), "{}", err.reason);
+}
+
+#[test]
+fn wrong_type_names_the_field() {
+    let contents = with_second(
+        r#"        "principal": "1000",
+        "annual_rate": "4.2",
+        "total_months": "twelve",
+        "start_date": "2024-01-15""#,
+    );
+
+    let err = schema_error(scenarios("type", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (15, Some("total_months")));
+}
+
+#[test]
+fn invalid_loan_terms_name_the_field() {
+    let contents = with_second(
+        r#"        "principal": "1000",
+        "annual_rate": "4.2",
+        "total_months": 12,
+        "start_date": "2024-01-15",
+        "balloon": "5000""#,
+    );
+
+    let err = schema_error(scenarios("balloon", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (17, Some("balloon")));
+    assert_eq!(err.to_string(), "line 17: field `balloon`: must be at least zero and less than principal");
+}
+
+#[test]
+fn missing_field_points_at_its_table() {
+    let contents = with_second(
+        r#"        "annual_rate": "4.2",
+        "total_months": 12,
+        "start_date": "2024-01-15""#,
+    );
+
+    let err = schema_error(scenarios("missing", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (12, Some("principal")));
+    assert_eq!(err.reason, "is required");
+}
+
+#[test]
+fn malformed_json_is_a_parse_error() {
+    let result = scenarios("syntax", &format!("[{},]", VALID));
+
+    assert!(matches!(result, Err(ScenarioError::Parse(_))));
+}
+
+fn yaml_scenarios(name: &str, contents: &str) -> Result<Vec<Scenario>, ScenarioError> {
+    let path = write(name, contents);
+    let result = load_scenario_yaml(&path);
+    fs::remove_file(path).unwrap();
+    result
+}
+
+const VALID_YAML: &str = "\
+# Two scenarios on the same terms.
+- loan:
+    principal: 120000
+    annual_rate: 4.2
+    total_months: 120
+    start_date: 2024-01-15
+  rate_changes:
+    - { from_period: 13, rate: 3.9 }
+  early_payments:
+    - period: 12
+      amount: 10000
+      strategy: ShortenTerm
+- loan:
+    principal: \"80000.50\"
+    annual_rate: 3.55
+    total_months: 60
+    start_date: 2024-03-31
+    method: EqualInstallment
+";
+
+#[test]
+fn loads_a_yaml_list_of_scenarios() {
+    let loaded = yaml_scenarios("valid", VALID_YAML).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[0].loan.annual_rate, dec!(4.2));
+    assert_eq!(loaded[0].rate_changes[0].rate, dec!(3.9));
+    assert_eq!(loaded[0].early_payments[0].amount, dec!(10000));
+    assert_eq!(loaded[1].loan.principal, dec!(80000.50));
+    assert!(loaded[1].run().is_ok());
+}
+
+#[test]
+fn yaml_single_scenario_matches_json() {
+    let yaml = yaml_scenarios(
+        "single",
+        "\
+loan:
+  principal: \"120000\"
+  annual_rate: \"4.2\"
+  total_months: 120
+  start_date: 2024-01-15
+early_payments:
+  - { period: 12, amount: 10000, strategy: ShortenTerm }
+",
+    )
+    .unwrap();
+    let path = write("single-json", VALID);
+    let json = load_scenario(&path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(yaml, [json]);
+}
+
+#[test]
+fn yaml_unknown_field_names_its_line() {
+    let contents = VALID_YAML.replace("    method: EqualInstallment", "    methd: EqualInstallment");
+
+    let err = schema_error(yaml_scenarios("unknown", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (18, Some("methd")));
+    assert!(err.reason.starts_with("unknown field `methd`"), "{}", err.reason);
+}
+
+#[test]
+fn yaml_wrong_type_names_the_field() {
+    let contents = VALID_YAML.replace("    total_months: 60", "    total_months: sixty");
+
+    let err = schema_error(yaml_scenarios("type", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (16, Some("total_months")));
+}
+
+#[test]
+fn yaml_invalid_loan_terms_name_the_field_in_their_scenario() {
+    // Both scenarios have an `annual_rate`; the error is on the second one's.
+    let contents = VALID_YAML.replace("    annual_rate: 3.55", "    annual_rate: 300");
+
+    let err = schema_error(yaml_scenarios("rate", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (15, Some("annual_rate")));
+    assert_eq!(err.reason, "must be at most 100");
+}
+
+#[test]
+fn yaml_missing_field_points_at_its_scenario() {
+    let contents = VALID_YAML.replace("    start_date: 2024-03-31\n", "");
+
+    let err = schema_error(yaml_scenarios("missing", &contents));
+
+    assert_eq!((err.line, err.field.as_deref()), (13, Some("start_date")));
+    assert_eq!(err.reason, "is required");
+}
+
+#[test]
+fn malformed_yaml_is_a_parse_error() {
+    let result = yaml_scenarios("syntax", "- loan: [\n    principal: 1\n");
+
+    assert!(matches!(result, Err(ScenarioError::ParseYaml(_))));
+}
@@ -0,0 +1,74 @@
+//! JSON persistence of `Loan` and `PaymentSchedule`.
+
+use chrono::NaiveDate;
+use prepayment::{
+    DayCount, Loan, LoanBuilder, PaymentSchedule, PenaltyRule, PrepaymentStrategy, RateMode, RepaymentMethod,
+    RoundingStrategy,
+};
+use rust_decimal_macros::dec;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn loan_round_trips_and_regenerates_the_same_schedule() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(250000.55))
+        .annual_rate(dec!(3.85))
+        .done_months(6)
+        .total_months(120)
+        .start_date(date(2024, 1, 31))
+        .method(RepaymentMethod::EqualInstallment)
+        .rate_mode(RateMode::Floating {
+            spread: dec!(-0.2),
+            resets: vec![(date(2025, 1, 1), dec!(3.6))],
+        })
+        .rate_floor(dec!(3))
+        .day_count(DayCount::Chinese30_360)
+        .prepayment_penalty(PenaltyRule::MonthsOfInterest(2))
+        .offset_changes(vec![(date(2026, 3, 1), dec!(10000))])
+        .rounding(RoundingStrategy::MidpointAwayFromZero)
+        .build()
+        .unwrap();
+
+    let restored = Loan::from_json(&loan.to_json()).unwrap();
+
+    assert_eq!(restored, loan);
+    assert_eq!(restored.generate_schedule(), loan.generate_schedule());
+}
+
+#[test]
+fn dates_are_iso_and_decimals_are_strings() {
+    let loan = LoanBuilder::new()
+        .principal(dec!(1000.10))
+        .annual_rate(dec!(4.2))
+        .total_months(12)
+        .start_date(date(2024, 10, 19))
+        .build()
+        .unwrap();
+    let json = loan.to_json();
+
+    assert!(json.contains(r#""principal":"1000.10""#), "{}", json);
+    assert!(json.contains(r#""annual_rate":"4.2""#), "{}", json);
+    assert!(json.contains(r#""start_date":"2024-10-19""#), "{}", json);
+}
+
+#[test]
+fn schedule_round_trips_with_early_payments() {
+    let mut loan = LoanBuilder::new()
+        .principal(dec!(12000))
+        .annual_rate(dec!(6))
+        .total_months(12)
+        .start_date(date(2024, 1, 15))
+        .build()
+        .unwrap();
+    let mut schedule = loan.generate_schedule();
+    loan.make_early_payment(dec!(1234.56), 4, PrepaymentStrategy::ReduceInstallment, &mut schedule)
+        .unwrap();
+
+    let json = serde_json::to_string(&schedule).unwrap();
+    let restored: Vec<PaymentSchedule> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored, schedule);
+}
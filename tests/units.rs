@@ -0,0 +1,58 @@
+//! The `Money` and `Period` newtypes and the API that accepts them.
+
+use chrono::NaiveDate;
+use prepayment::{LoanBuilder, Money, Period, PrepaymentStrategy};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+#[test]
+fn typed_and_raw_arguments_give_the_same_schedule() {
+    let mut loan = LoanBuilder::new()
+        .principal(Money(dec!(12000)))
+        .annual_rate(dec!(6))
+        .done_months(2)
+        .total_months(12)
+        .start_date(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap())
+        .build()
+        .unwrap();
+    let mut typed = loan.generate_schedule();
+    let mut raw = typed.clone();
+
+    loan.clone().adjust_rate(dec!(4.8), Period(5), &mut typed).unwrap();
+    loan.clone().adjust_rate(dec!(4.8), 5, &mut raw).unwrap();
+    loan.make_early_payment(Money(dec!(1500)), Period(6), PrepaymentStrategy::ShortenTerm, &mut typed).unwrap();
+    loan.make_early_payment(dec!(1500), 6, PrepaymentStrategy::ShortenTerm, &mut raw).unwrap();
+    loan.add_fee(Period(7), Money(dec!(25)), &mut typed).unwrap();
+    loan.add_fee(7, dec!(25), &mut raw).unwrap();
+
+    assert_eq!(typed, raw);
+    assert_eq!(loan.row_for_period(&typed, Period(6)), loan.row_for_period(&raw, 6));
+}
+
+#[test]
+fn money_arithmetic() {
+    let total: Money = [Money(dec!(1.10)), Money(dec!(2.20)), -Money(dec!(0.30))].into_iter().sum();
+    assert_eq!(total, Money(dec!(3.00)));
+
+    let mut balance = Money::from(dec!(100));
+    balance -= Money(dec!(40));
+    balance += Money(dec!(5));
+    assert_eq!(Decimal::from(balance), dec!(65));
+    assert_eq!(balance.to_string(), "65");
+}
+
+#[test]
+fn period_arithmetic_saturates() {
+    assert_eq!(Period(10) + 3, Period(13));
+    assert_eq!(Period(13) - Period(10), 3);
+    assert_eq!(Period(10) - Period(13), 0);
+    assert_eq!(Period(u32::MAX) + 1, Period(u32::MAX));
+    assert_eq!(u32::from(Period::from(7)), 7);
+}
+
+#[test]
+fn newtypes_serialize_transparently() {
+    assert_eq!(serde_json::to_string(&Money(dec!(12.50))).unwrap(), r#""12.50""#);
+    assert_eq!(serde_json::to_string(&Period(61)).unwrap(), "61");
+    assert_eq!(serde_json::from_str::<Period>("61").unwrap(), Period(61));
+}